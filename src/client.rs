@@ -29,22 +29,57 @@ mod errors {
         KeyError {
             source: E,
         },
+        Unauthenticated {
+            source: crate::resources::UnauthenticatedError,
+        },
+        #[snafu(display("key does not refer to a comment"))]
+        NotAComment,
+        #[snafu(display("rejected by FurAffinity: {}", message))]
+        Rejected {
+            message: String,
+        },
+        #[snafu(display("the user does not accept shouts"))]
+        ShoutsDisabled,
+        #[snafu(display("the comment did not show up after posting"))]
+        NotPosted,
+        #[snafu(display("{} has no FurAffinity id", name))]
+        UnknownTaxonomy {
+            name: String,
+        },
+        #[snafu(display("{} is not on FurAffinity's CDN", url))]
+        NotCdn {
+            url: url::Url,
+        },
     }
 }
 
 use crate::keys::{
-    CommentReplyKey, FavKey, FromStrError, FromUrlError, JournalKey,
-    SubmissionsKey, ViewKey,
+    BrowseKey, CommentReplyKey, FavKey, FavoritesKey, FolderKey, FromStrError,
+    FromUrlError, JournalKey, SubmissionsKey, UserKey, ViewKey,
 };
+use crate::resources::browse::Browse;
+use crate::resources::comment::{CommentContext, ReplyForm};
+use crate::resources::favorites::Favorites;
+use crate::resources::folder::Folder;
 use crate::resources::header::Header;
 use crate::resources::journal::Journal;
 use crate::resources::msg::others::Others;
 use crate::resources::msg::submissions::Submissions;
-use crate::resources::view::View;
-use crate::resources::{FromHtml, ParseError};
+use crate::resources::shout::ShoutForm;
+use crate::resources::upload::{FinalizeForm, UploadForm};
+use crate::resources::view::{Category, Type, View};
+use crate::resources::{
+    FromHtml, MiniUser, ParseError, Rating, UnauthenticatedError,
+};
+
+use bytes::Bytes;
+
+use futures::future::BoxFuture;
+use futures::stream::{self, Stream, StreamExt};
 
-use reqwest::header::{HeaderMap, HeaderValue, COOKIE};
-use reqwest::ClientBuilder;
+use reqwest::header::{HeaderValue, COOKIE, LOCATION, USER_AGENT};
+use reqwest::multipart::{Form, Part};
+use reqwest::Method;
 
 use scraper::Html;
 
@@ -52,9 +87,12 @@ pub use self::errors::{ClientError, RequestError};
 
 use serde::Serialize;
 
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 
-use std::convert::{Infallible, TryInto};
+use std::convert::{Infallible, TryFrom, TryInto};
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 
 use tokio::sync::RwLock;
 
@@ -70,6 +108,19 @@ impl From<RequestError<Infallible>> for RequestError<FromStrError> {
                 RequestError::Reqwest { source }
             }
             RequestError::Parse { source } => RequestError::Parse { source },
+            RequestError::Unauthenticated { source } => {
+                RequestError::Unauthenticated { source }
+            }
+            RequestError::NotAComment => RequestError::NotAComment,
+            RequestError::Rejected { message } => {
+                RequestError::Rejected { message }
+            }
+            RequestError::ShoutsDisabled => RequestError::ShoutsDisabled,
+            RequestError::NotPosted => RequestError::NotPosted,
+            RequestError::UnknownTaxonomy { name } => {
+                RequestError::UnknownTaxonomy { name }
+            }
+            RequestError::NotCdn { url } => RequestError::NotCdn { url },
             RequestError::KeyError { .. } => unreachable!(),
         }
     }
@@ -85,38 +136,70 @@ impl From<RequestError<Infallible>> for RequestError<FromUrlError> {
                 RequestError::Reqwest { source }
             }
             RequestError::Parse { source } => RequestError::Parse { source },
+            RequestError::Unauthenticated { source } => {
+                RequestError::Unauthenticated { source }
+            }
+            RequestError::NotAComment => RequestError::NotAComment,
+            RequestError::Rejected { message } => {
+                RequestError::Rejected { message }
+            }
+            RequestError::ShoutsDisabled => RequestError::ShoutsDisabled,
+            RequestError::NotPosted => RequestError::NotPosted,
+            RequestError::UnknownTaxonomy { name } => {
+                RequestError::UnknownTaxonomy { name }
+            }
+            RequestError::NotCdn { url } => RequestError::NotCdn { url },
             RequestError::KeyError { .. } => unreachable!(),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Authentication {
+    LoggedIn,
+    Anonymous,
+}
+
 #[derive(Debug)]
 pub struct Response<V> {
+    pub final_url: Url,
+    pub authentication: Authentication,
+    /// `None` when logged out, or when logged in but the header could not be
+    /// parsed. Check `authentication` to tell the two apart.
     pub header: Option<Header>,
     pub page: V,
 }
 
+impl<V> Response<V> {
+    pub fn is_authenticated(&self) -> bool {
+        self.authentication == Authentication::LoggedIn
+    }
+}
+
 impl<V> Response<V>
 where
     V: FromHtml,
 {
+    /// With `require_auth`, a logged-out page fails with `Unauthenticated`
+    /// before it's parsed, rather than coming back as `Anonymous`.
     async fn from_response<E>(
         response: reqwest::Response,
+        require_auth: bool,
     ) -> Result<Self, RequestError<E>>
     where
         E: 'static + std::error::Error,
     {
-        ensure!(
-            response.status().is_success(),
-            errors::Unsuccessful {
-                status: response.status()
-            },
-        );
+        ensure_success(&response)?;
 
         let url = response.url().clone();
         let text = response.text().await?;
         let html = Html::parse_document(&text);
-        Ok(Self::from_html(url, &html).context(errors::Parse)?)
+
+        if require_auth && !crate::resources::header::is_logged_in(&html) {
+            return Err(UnauthenticatedError).context(errors::Unauthenticated);
+        }
+
+        Self::from_html(url, &html).context(errors::Parse)
     }
 }
 
@@ -125,16 +208,393 @@ where
     V: FromHtml,
 {
     fn from_html(url: Url, html: &Html) -> Result<Self, ParseError> {
+        if crate::resources::is_registration_required(&url, html) {
+            return Err(ParseError::RegistrationRequired);
+        }
+
+        let authentication = if crate::resources::header::is_logged_in(html) {
+            Authentication::LoggedIn
+        } else {
+            Authentication::Anonymous
+        };
+
         Ok(Self {
+            authentication,
             header: Header::from_html(url.clone(), html).ok(),
-            page: V::from_html(url, html)?,
+            page: V::from_html(url.clone(), html)?,
+            final_url: url,
+        })
+    }
+}
+
+fn is_cdn(url: &Url) -> bool {
+    let is_http = matches!(url.scheme(), "http" | "https");
+    let host = url.host_str().unwrap_or_default();
+    is_http && (host == "facdn.net" || host.ends_with(".facdn.net"))
+}
+
+fn ensure_landed<K, E>(
+    response: &reqwest::Response,
+    expected: K,
+) -> Result<(), RequestError<E>>
+where
+    K: PartialEq + for<'a> TryFrom<&'a Url>,
+    E: 'static + std::error::Error,
+{
+    match K::try_from(response.url()) {
+        Ok(landed) if landed == expected => Ok(()),
+        _ if response.url().path().starts_with("/login") => {
+            Err(RequestError::Parse {
+                source: ParseError::RegistrationRequired,
+            })
+        }
+        _ => Err(RequestError::Parse {
+            source: ParseError::Redirected {
+                to: response.url().clone(),
+            },
+        }),
+    }
+}
+
+/// Fails unless the response was successful. Redirects only reach here when
+/// the client isn't following them, and are reported with where they lead.
+fn ensure_success<E>(
+    response: &reqwest::Response,
+) -> Result<(), RequestError<E>>
+where
+    E: 'static + std::error::Error,
+{
+    let status = response.status();
+
+    if status.is_redirection() {
+        let location = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|l| l.to_str().ok())
+            .and_then(|l| response.url().join(l).ok());
+
+        if let Some(to) = location {
+            let source = if to.path().starts_with("/login") {
+                ParseError::RegistrationRequired
+            } else {
+                ParseError::Redirected { to }
+            };
+            return Err(RequestError::Parse { source });
+        }
+    }
+
+    ensure!(status.is_success(), errors::Unsuccessful { status });
+    Ok(())
+}
+
+/// Fails with the text of FurAffinity's system message, if the response
+/// contains one instead of the expected page.
+async fn ensure_accepted<E>(
+    response: reqwest::Response,
+) -> Result<(), RequestError<E>>
+where
+    E: 'static + std::error::Error,
+{
+    ensure_success(&response)?;
+
+    let text = response.text().await?;
+    let html = Html::parse_document(&text);
+
+    match crate::resources::system_message(&html) {
+        Some(message) => errors::Rejected { message }.fail(),
+        None => Ok(()),
+    }
+}
+
+/// Sends the requests a [`Client`] builds.
+///
+/// `reqwest::Client` is the default. A custom transport can route requests
+/// through a proxy, serve them from a cache, or answer them with fixture HTML
+/// in tests. Requests already carry the user agent, timeout and, when
+/// authenticated, the session cookies.
+pub trait HttpTransport: fmt::Debug + Send + Sync {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, reqwest::Result<reqwest::Response>>;
+}
+
+impl HttpTransport for reqwest::Client {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+        Box::pin(reqwest::Client::execute(self, request))
+    }
+}
+
+/// A new submission for [`Client::upload`].
+///
+/// Category and type default to "Artwork (Digital)" and "All". Species and
+/// gender are FurAffinity's numeric ids from the submission form, and
+/// default to "Unspecified" and "Any".
+#[derive(Debug, Clone)]
+pub struct UploadParams {
+    file_name: String,
+    file: Vec<u8>,
+    title: String,
+    description: String,
+    tags: Vec<String>,
+    rating: Rating,
+    category: Category,
+    theme: Type,
+    species: u16,
+    gender: u16,
+}
+
+impl UploadParams {
+    const SPECIES_UNSPECIFIED: u16 = 1;
+    const GENDER_ANY: u16 = 0;
+
+    pub fn new<F, T>(file_name: F, file: Vec<u8>, title: T) -> Self
+    where
+        F: Into<String>,
+        T: Into<String>,
+    {
+        Self {
+            file_name: file_name.into(),
+            file,
+            title: title.into(),
+            description: String::new(),
+            tags: Vec::new(),
+            rating: Rating::General,
+            category: Category::ArtworkDigital,
+            theme: Type::All,
+            species: Self::SPECIES_UNSPECIFIED,
+            gender: Self::GENDER_ANY,
+        }
+    }
+
+    pub fn with_description<S>(mut self, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.description = description.into();
+        self
+    }
+
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    pub fn with_rating(mut self, rating: Rating) -> Self {
+        self.rating = rating;
+        self
+    }
+
+    /// `Category::Other` has no id, so uploading with it fails with
+    /// [`RequestError::UnknownTaxonomy`].
+    pub fn with_category(mut self, category: Category) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// `Type::Other` has no id, so uploading with it fails with
+    /// [`RequestError::UnknownTaxonomy`].
+    pub fn with_theme(mut self, theme: Type) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    pub fn with_species(mut self, species: u16) -> Self {
+        self.species = species;
+        self
+    }
+
+    pub fn with_gender(mut self, gender: u16) -> Self {
+        self.gender = gender;
+        self
+    }
+
+    fn finalize_form<E>(&self) -> Result<Vec<(String, String)>, RequestError<E>>
+    where
+        E: 'static + std::error::Error,
+    {
+        let rating = match self.rating {
+            Rating::General => "0",
+            Rating::Adult => "1",
+            Rating::Mature => "2",
+        };
+
+        let category = self.category.id().context(errors::UnknownTaxonomy {
+            name: self.category.to_string(),
+        })?;
+        let theme = self.theme.id().context(errors::UnknownTaxonomy {
+            name: self.theme.to_string(),
+        })?;
+
+        Ok(vec![
+            ("title".into(), self.title.clone()),
+            ("message".into(), self.description.clone()),
+            ("keywords".into(), self.tags.join(" ")),
+            ("rating".into(), rating.into()),
+            ("cat".into(), category.to_string()),
+            ("atype".into(), theme.to_string()),
+            ("species".into(), self.species.to_string()),
+            ("gender".into(), self.gender.to_string()),
+        ])
+    }
+}
+
+/// How the default transport handles redirects.
+///
+/// When a redirect isn't followed, requests fail with
+/// [`ParseError::Redirected`], or [`ParseError::RegistrationRequired`] if it
+/// leads to the login page, instead of silently parsing wherever FurAffinity
+/// sent them (like the front page, for a deleted submission).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum Redirects {
+    /// Follow redirects, up to `reqwest`'s default limit.
+    #[default]
+    Follow,
+    /// Follow at most this many redirects per request.
+    Limit(usize),
+    Never,
+}
+
+impl Redirects {
+    fn policy(self) -> reqwest::redirect::Policy {
+        match self {
+            Redirects::Follow => reqwest::redirect::Policy::default(),
+            Redirects::Limit(max) => reqwest::redirect::Policy::limited(max),
+            Redirects::Never => reqwest::redirect::Policy::none(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    user_agent: String,
+    timeout: Option<Duration>,
+    cookies: Option<HeaderValue>,
+    redirects: Redirects,
+    require_auth: bool,
+    transport: Option<Arc<dyn HttpTransport>>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            user_agent: Client::USER_AGENT.to_string(),
+            timeout: None,
+            cookies: None,
+            redirects: Redirects::default(),
+            require_auth: false,
+            transport: None,
+        }
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn user_agent<S>(mut self, user_agent: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn cookies<H>(mut self, cookies: H) -> Self
+    where
+        H: Into<HeaderValue>,
+    {
+        self.cookies = Some(cookies.into());
+        self
+    }
+
+    /// Only applies to the default transport. A custom transport decides
+    /// for itself whether to follow redirects.
+    pub fn redirects(mut self, redirects: Redirects) -> Self {
+        self.redirects = redirects;
+        self
+    }
+
+    /// Fail with [`RequestError::Unauthenticated`] when a page fetched with
+    /// the session's cookies comes back logged out, like after the session
+    /// expires mid-crawl, instead of quietly parsing what a guest would see.
+    ///
+    /// Doesn't apply to `view_public`, which is always logged out.
+    pub fn require_auth(mut self, require_auth: bool) -> Self {
+        self.require_auth = require_auth;
+        self
+    }
+
+    /// Send every request through `transport` instead of `reqwest`.
+    ///
+    /// Anonymous requests, like those from `view_public`, go through the
+    /// same transport without the session cookies, so it must not add
+    /// cookies of its own.
+    pub fn transport<T>(mut self, transport: T) -> Self
+    where
+        T: 'static + HttpTransport,
+    {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    fn http(&self) -> Result<reqwest::Client, ClientError> {
+        let client = reqwest::ClientBuilder::new()
+            .cookie_store(true)
+            .redirect(self.redirects.policy())
+            .build()?;
+        Ok(client)
+    }
+
+    pub fn build(self) -> Result<Client, ClientError> {
+        // Separate clients keep cookies set on the session's responses out
+        // of anonymous requests. Requests are built with the session's
+        // client, which shares its pool with the transport. A custom
+        // transport still needs a client to build requests, but it never
+        // sends any.
+        let (transport, anonymous, requests) = match &self.transport {
+            Some(t) => (t.clone(), t.clone(), reqwest::Client::new()),
+            None => {
+                let session = self.http()?;
+                let transport: Arc<dyn HttpTransport> =
+                    Arc::new(session.clone());
+                let anonymous: Arc<dyn HttpTransport> = Arc::new(self.http()?);
+                (transport, anonymous, session)
+            }
+        };
+
+        Ok(Client {
+            transport,
+            anonymous,
+            requests,
+            cookies: RwLock::new(self.cookies.clone()),
+            config: self,
         })
     }
 }
 
 #[derive(Debug)]
 pub struct Client {
-    client: RwLock<reqwest::Client>,
+    transport: Arc<dyn HttpTransport>,
+    anonymous: Arc<dyn HttpTransport>,
+    /// Only used to build requests, which are sent through `transport`. The
+    /// same client as `transport` unless a custom one was given.
+    requests: reqwest::Client,
+    cookies: RwLock<Option<HeaderValue>>,
+    config: ClientBuilder,
 }
 
 impl Client {
@@ -145,45 +605,93 @@ impl Client {
         " (vypo@fursuits.by)",
     );
 
-    fn builder() -> ClientBuilder {
+    pub fn builder() -> ClientBuilder {
         ClientBuilder::new()
-            .cookie_store(true)
-            .user_agent(Self::USER_AGENT)
     }
 
     pub fn new() -> Result<Self, ClientError> {
-        let builder = Self::builder();
-        Ok(Self {
-            client: RwLock::new(builder.build()?),
-        })
+        Self::builder().build()
     }
 
     pub fn with_cookies<H>(cookies: H) -> Result<Self, ClientError>
     where
         H: Into<HeaderValue>,
     {
-        let mut headers = HeaderMap::new();
-        headers.insert(COOKIE, cookies.into());
-
-        let builder = Self::builder().default_headers(headers);
-
-        Ok(Self {
-            client: RwLock::new(builder.build()?),
-        })
+        Self::builder().cookies(cookies).build()
     }
 
     pub async fn set_cookies<H>(&self, cookies: H) -> Result<(), ClientError>
     where
         H: Into<HeaderValue>,
     {
-        let mut headers = HeaderMap::new();
-        headers.insert(COOKIE, cookies.into());
-
-        let mut client = self.client.write().await;
-        *client = Self::builder().default_headers(headers).build()?;
+        *self.cookies.write().await = Some(cookies.into());
         Ok(())
     }
 
+    fn request(&self, method: Method, url: Url) -> reqwest::RequestBuilder {
+        let mut builder = self
+            .requests
+            .request(method, url)
+            .header(USER_AGENT, self.config.user_agent.as_str());
+
+        if let Some(timeout) = self.config.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        builder
+    }
+
+    fn get(&self, url: Url) -> reqwest::RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    fn post(&self, url: Url) -> reqwest::RequestBuilder {
+        self.request(Method::POST, url)
+    }
+
+    async fn send(
+        &self,
+        builder: reqwest::RequestBuilder,
+    ) -> reqwest::Result<reqwest::Response> {
+        let mut request = builder.build()?;
+
+        if let Some(cookies) = self.cookies.read().await.as_ref() {
+            request.headers_mut().insert(COOKIE, cookies.clone());
+        }
+
+        self.transport.execute(request).await
+    }
+
+    async fn send_anonymous(
+        &self,
+        builder: reqwest::RequestBuilder,
+    ) -> reqwest::Result<reqwest::Response> {
+        self.anonymous.execute(builder.build()?).await
+    }
+
+    /// Whether the session cookies are still accepted, returning the
+    /// logged-in user if they are and `None` if the session has expired.
+    pub async fn check_session(
+        &self,
+    ) -> Result<Option<MiniUser>, RequestError<Infallible>> {
+        let url = Url::parse("https://www.furaffinity.net/").unwrap();
+
+        let response = self.send(self.get(url)).await?;
+
+        ensure_success(&response)?;
+
+        let url = response.url().clone();
+        let text = response.text().await?;
+        let html = Html::parse_document(&text);
+
+        if !crate::resources::header::is_logged_in(&html) {
+            return Ok(None);
+        }
+
+        let header = Header::from_html(url, &html).context(errors::Parse)?;
+        Ok(Some(header.me().clone()))
+    }
+
     pub async fn journal<K>(
         &self,
         key: K,
@@ -195,8 +703,9 @@ impl Client {
         let key = key.try_into().context(errors::KeyError)?;
         let url = Url::from(key);
 
-        let response = self.client.read().await.get(url.clone()).send().await?;
-        Response::from_response(response).await
+        let response = self.send(self.get(url.clone())).await?;
+        ensure_landed(&response, key)?;
+        Response::from_response(response, self.config.require_auth).await
     }
 
     pub async fn view<K>(
@@ -210,14 +719,391 @@ impl Client {
         let key = key.try_into().context(errors::KeyError)?;
         let url = Url::from(key);
 
-        let response = self.client.read().await.get(url.clone()).send().await?;
-        Response::from_response(response).await
+        let response = self.send(self.get(url.clone())).await?;
+        ensure_landed(&response, key)?;
+        Response::from_response(response, self.config.require_auth).await
+    }
+
+    /// Fetch a submission without sending the session cookies, as a
+    /// logged-out visitor would see it. The parsed view never has a
+    /// `FavKey`, and mature or adult submissions fail with `Nsfw`.
+    pub async fn view_public<K>(
+        &self,
+        key: K,
+    ) -> Result<Response<View>, RequestError<K::Error>>
+    where
+        K: TryInto<ViewKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = key.try_into().context(errors::KeyError)?;
+        let url = Url::from(key);
+
+        let response = self.send_anonymous(self.get(url)).await?;
+        ensure_landed(&response, key)?;
+        Response::from_response(response, false).await
+    }
+
+    /// Fetch several submissions at once, with at most `concurrency`
+    /// requests in flight. Responses are yielded as they complete, which is
+    /// not necessarily the order of `keys`. A `concurrency` of 0 is treated
+    /// as 1.
+    pub fn views<'a, I>(
+        &'a self,
+        keys: I,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<Response<View>, RequestError<Infallible>>> + 'a
+    where
+        I: IntoIterator<Item = ViewKey>,
+        I::IntoIter: 'a,
+    {
+        stream::iter(keys)
+            .map(move |key| self.view(key))
+            .buffer_unordered(concurrency.max(1))
+    }
+
+    /// Request the submission's file using the session's cookies. The body
+    /// is left unread so it can be streamed. Like [`fetch_cdn`], a download
+    /// link off FurAffinity's CDN fails with [`RequestError::NotCdn`].
+    ///
+    /// [`fetch_cdn`]: Self::fetch_cdn
+    pub async fn download(
+        &self,
+        view: &View,
+    ) -> Result<reqwest::Response, RequestError<Infallible>> {
+        let url = view.download().clone();
+        ensure!(is_cdn(&url), errors::NotCdn { url });
+
+        let response = self.send(self.get(url)).await?;
+
+        ensure_success(&response)?;
+
+        Ok(response)
+    }
+
+    /// Fetch a file from FurAffinity's CDN, like a preview or an avatar,
+    /// using the session's cookies. URLs on any other host fail with
+    /// [`RequestError::NotCdn`] without a request being made, so the
+    /// cookies are never sent off-site.
+    pub async fn fetch_cdn(
+        &self,
+        url: &Url,
+    ) -> Result<Bytes, RequestError<Infallible>> {
+        ensure!(is_cdn(url), errors::NotCdn { url: url.clone() });
+
+        let response = self.send(self.get(url.clone())).await?;
+
+        ensure_success(&response)?;
+
+        Ok(response.bytes().await?)
+    }
+
+    /// Fetch the text of a plain-text story. Returns `None` for other
+    /// submissions, including text submissions in other formats.
+    pub async fn story_text(
+        &self,
+        view: &View,
+    ) -> Result<Option<String>, RequestError<Infallible>> {
+        let is_txt = view
+            .download_filename()
+            .map(|f| f.to_lowercase().ends_with(".txt"))
+            .unwrap_or(false);
+
+        if !view.is_downloadable_document() || !is_txt {
+            return Ok(None);
+        }
+
+        let response = self.download(view).await?;
+        Ok(Some(response.text().await?))
+    }
+
+    /// Fetch the comment `key` points at, along with the comments it replies
+    /// to. FurAffinity's reply page redirects to the submission or journal
+    /// the comment belongs to, which is how the root is found.
+    pub async fn comment_context<K>(
+        &self,
+        key: K,
+    ) -> Result<CommentContext, RequestError<K::Error>>
+    where
+        K: TryInto<CommentReplyKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = key.try_into().context(errors::KeyError)?;
+        let comment_id = key.comment_id().context(errors::NotAComment)?;
+
+        let response = self.send(self.get(Url::from(&key))).await?;
+
+        ensure_success(&response)?;
+
+        let url = response.url().clone();
+        let text = response.text().await?;
+        let html = Html::parse_document(&text);
+
+        let comments = if ViewKey::try_from(&url).is_ok() {
+            let view = View::from_html(url, &html).context(errors::Parse)?;
+            view.comment_thread(comment_id)
+                .map(|t| t.into_iter().cloned().collect::<Vec<_>>())
+        } else if JournalKey::try_from(&url).is_ok() {
+            let journal =
+                Journal::from_html(url, &html).context(errors::Parse)?;
+            journal
+                .comment_thread(comment_id)
+                .map(|t| t.into_iter().cloned().collect::<Vec<_>>())
+        } else {
+            return Err(RequestError::Parse {
+                source: ParseError::Redirected { to: url },
+            });
+        };
+
+        let thread = comments
+            .ok_or(ParseError::MissingElement {
+                selector: "a.comment_anchor",
+            })
+            .context(errors::Parse)?;
+
+        Ok(CommentContext { thread })
     }
 
+    /// Post a comment, either at the top level of a submission or journal, or
+    /// as a reply to an existing comment. Returns the id of the new comment.
+    ///
+    /// The page is fetched first so the comment form's hidden fields, like
+    /// FurAffinity's anti-spam token, can be posted back. The new comment is
+    /// the user's newest one after posting, which must be newer than any
+    /// they had before, or this fails with [`RequestError::NotPosted`].
     pub async fn reply<K>(
         &self,
         to: K,
         comment: &str,
+    ) -> Result<u64, RequestError<K::Error>>
+    where
+        K: TryInto<CommentReplyKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = to.try_into().context(errors::KeyError)?;
+        let url = Url::from(&key);
+
+        let response = self.send(self.get(url)).await?;
+
+        ensure_success(&response)?;
+
+        let final_url = response.url().clone();
+        let body = response.text().await?;
+
+        // Scoped so the parsed page, which isn't `Send`, is gone before the
+        // next request.
+        let (form, before) = {
+            let html = Html::parse_document(&body);
+
+            let before = match Header::from_html(final_url.clone(), &html) {
+                Ok(h) => {
+                    crate::resources::comment::newest_by(&html, h.me().slug())
+                        .ok()
+                }
+                Err(_) => None,
+            };
+
+            let form = match ReplyForm::from_html(final_url, &html) {
+                Ok(f) => f,
+                Err(ParseError::MissingElement { .. }) => {
+                    if let Some(message) =
+                        crate::resources::system_message(&html)
+                    {
+                        return errors::Rejected { message }.fail();
+                    }
+
+                    if !crate::resources::header::is_logged_in(&html) {
+                        return Err(UnauthenticatedError)
+                            .context(errors::Unauthenticated);
+                    }
+
+                    return Err(RequestError::Parse {
+                        source: ParseError::MissingElement {
+                            selector: "form textarea[name='reply']",
+                        },
+                    });
+                }
+                Err(e) => return Err(RequestError::Parse { source: e }),
+            };
+
+            (form, before)
+        };
+
+        let reply_to = key.comment_id().map(|c| c.to_string());
+
+        let mut fields = form.fields;
+        fields.retain(|(name, _)| name != "replyto");
+        fields.push(("replyto".to_string(), reply_to.unwrap_or_default()));
+        fields.push(("reply".to_string(), comment.to_string()));
+        fields.push(("send".to_string(), "send".to_string()));
+
+        let response = self.send(self.post(form.action).form(&fields)).await?;
+
+        ensure_success(&response)?;
+
+        let final_url = response.url().clone();
+        let text = response.text().await?;
+        let html = Html::parse_document(&text);
+
+        if let Some(message) = crate::resources::system_message(&html) {
+            return errors::Rejected { message }.fail();
+        }
+
+        let header =
+            Header::from_html(final_url, &html).context(errors::Parse)?;
+
+        let newest =
+            crate::resources::comment::newest_by(&html, header.me().slug());
+        let comment_id = match newest {
+            Ok(c) => c,
+            Err(ParseError::MissingElement { .. }) => {
+                return errors::NotPosted.fail()
+            }
+            Err(e) => return Err(RequestError::Parse { source: e }),
+        };
+
+        // A comment the user left earlier doesn't prove this one was posted.
+        if let Some(before) = before {
+            ensure!(comment_id > before, errors::NotPosted);
+        }
+
+        Ok(comment_id)
+    }
+
+    /// Leave a shout on `user`'s profile. The profile is fetched first for
+    /// the form's key.
+    pub async fn shout<K>(
+        &self,
+        user: K,
+        text: &str,
+    ) -> Result<(), RequestError<K::Error>>
+    where
+        K: TryInto<UserKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = user.try_into().context(errors::KeyError)?;
+        let url = Url::from(&key);
+
+        let response = self.send(self.get(url)).await?;
+
+        ensure_success(&response)?;
+
+        let final_url = response.url().clone();
+        let body = response.text().await?;
+
+        let form = {
+            let html = Html::parse_document(&body);
+
+            match ShoutForm::from_html(final_url, &html) {
+                Ok(f) => f,
+                Err(ParseError::MissingElement { .. }) => {
+                    if let Some(message) =
+                        crate::resources::system_message(&html)
+                    {
+                        return errors::Rejected { message }.fail();
+                    }
+
+                    // Without a session there's no form at all, so only blame
+                    // the profile's settings when logged in.
+                    if !crate::resources::header::is_logged_in(&html) {
+                        return Err(UnauthenticatedError)
+                            .context(errors::Unauthenticated);
+                    }
+
+                    return errors::ShoutsDisabled.fail();
+                }
+                Err(e) => return Err(RequestError::Parse { source: e }),
+            }
+        };
+
+        let mut fields = form.fields;
+        fields.push(("shout".to_string(), text.to_string()));
+
+        let response = self.send(self.post(form.action).form(&fields)).await?;
+        ensure_accepted(response).await
+    }
+
+    /// Submit new artwork, returning the key of the new submission.
+    ///
+    /// This walks through FurAffinity's submission form: the file is uploaded
+    /// first, then the details are filled in. A system message at any step,
+    /// like a file that's too large, fails with [`RequestError::Rejected`].
+    pub async fn upload(
+        &self,
+        params: UploadParams,
+    ) -> Result<ViewKey, RequestError<Infallible>> {
+        // Checked before anything is uploaded.
+        let details = params.finalize_form()?;
+
+        let url = Url::parse("https://www.furaffinity.net/submit/").unwrap();
+
+        let form: UploadForm = self.fetch_form(self.get(url)).await?;
+
+        let file = Part::bytes(params.file).file_name(params.file_name);
+        let multipart = form
+            .fields
+            .into_iter()
+            .fold(Form::new(), |m, (name, value)| m.text(name, value))
+            .part("submission", file);
+        let request = self.post(form.action).multipart(multipart);
+
+        let form: FinalizeForm = self.fetch_form(request).await?;
+
+        let mut fields = form.fields;
+        fields.retain(|(name, _)| !details.iter().any(|(n, _)| n == name));
+        fields.extend(details);
+
+        let response = self.send(self.post(form.action).form(&fields)).await?;
+        ensure_success(&response)?;
+
+        if let Ok(key) = ViewKey::try_from(response.url()) {
+            return Ok(key);
+        }
+
+        let to = response.url().clone();
+        let text = response.text().await?;
+        let html = Html::parse_document(&text);
+
+        if let Some(message) = crate::resources::system_message(&html) {
+            return errors::Rejected { message }.fail();
+        }
+
+        Err(RequestError::Parse {
+            source: ParseError::Redirected { to },
+        })
+    }
+
+    /// Sends `request` and parses the form on the page it lands on, failing
+    /// with the page's system message if it has one.
+    async fn fetch_form<F, E>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<F, RequestError<E>>
+    where
+        F: FromHtml,
+        E: 'static + std::error::Error,
+    {
+        let response = self.send(request).await?;
+        ensure_success(&response)?;
+
+        let final_url = response.url().clone();
+        let text = response.text().await?;
+        let html = Html::parse_document(&text);
+
+        if crate::resources::is_registration_required(&final_url, &html) {
+            return Err(UnauthenticatedError).context(errors::Unauthenticated);
+        }
+
+        if let Some(message) = crate::resources::system_message(&html) {
+            return errors::Rejected { message }.fail();
+        }
+
+        F::from_html(final_url, &html).context(errors::Parse)
+    }
+
+    pub async fn edit_comment<K>(
+        &self,
+        key: K,
+        new_text: &str,
     ) -> Result<(), RequestError<K::Error>>
     where
         K: TryInto<CommentReplyKey>,
@@ -225,43 +1111,79 @@ impl Client {
     {
         #[derive(Serialize)]
         struct Form<'a> {
-            reply: &'a str,
-            replyto: &'a str,
             action: &'a str,
-            send: &'a str,
+            reply: &'a str,
         }
 
-        let key = to.try_into().context(errors::KeyError)?;
-        let url = Url::from(key);
+        let key = key.try_into().context(errors::KeyError)?;
+        let url = key.edit_url().context(errors::NotAComment)?;
 
         let form = Form {
-            action: "reply",
-            reply: comment,
-            replyto: "",
-            send: "send",
+            action: "edit",
+            reply: new_text,
         };
 
+        let response = self.send(self.post(url).form(&form)).await?;
+
+        ensure_accepted(response).await
+    }
+
+    /// Remove one of the session's own comments, checking that the page
+    /// FurAffinity returns no longer shows it.
+    pub async fn delete_comment<K>(
+        &self,
+        key: K,
+    ) -> Result<(), RequestError<K::Error>>
+    where
+        K: TryInto<CommentReplyKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        #[derive(Serialize)]
+        struct Form<'a> {
+            action: &'a str,
+        }
+
+        let key = key.try_into().context(errors::KeyError)?;
+        let url = key.delete_url().context(errors::NotAComment)?;
+        let comment_id = key.comment_id().context(errors::NotAComment)?;
+
         let response = self
-            .client
-            .read()
-            .await
-            .post(url.clone())
-            .form(&form)
-            .send()
+            .send(self.post(url).form(&Form { action: "delete" }))
             .await?;
 
+        ensure_success(&response)?;
+
+        let text = response.text().await?;
+        let html = Html::parse_document(&text);
+
+        if let Some(message) = crate::resources::system_message(&html) {
+            return errors::Rejected { message }.fail();
+        }
+
         ensure!(
-            response.status().is_success(),
-            errors::Unsuccessful {
-                status: response.status()
-            },
+            !crate::resources::comment::is_visible(&html, comment_id),
+            errors::Rejected {
+                message: "comment is still visible after deletion",
+            }
         );
 
-        // TODO: check for errors in the HTML
-
         Ok(())
     }
 
+    pub async fn fav_key<K>(
+        &self,
+        view: K,
+    ) -> Result<FavKey, RequestError<K::Error>>
+    where
+        K: TryInto<ViewKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let response = self.view(view).await?;
+        FavKey::try_from(response.page).context(errors::Unauthenticated)
+    }
+
+    /// Favorites a submission. FurAffinity answers with the submission page,
+    /// so the returned view already reflects the new favorite.
     pub async fn fav<K>(
         &self,
         view: K,
@@ -284,6 +1206,30 @@ impl Client {
         self.maybe_fav(view, false).await
     }
 
+    pub async fn fav_view<K>(
+        &self,
+        view: K,
+    ) -> Result<Response<View>, RequestError<K::Error>>
+    where
+        K: TryInto<ViewKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = self.fav_key(view).await?;
+        self.send_fav(&key, true).await
+    }
+
+    pub async fn unfav_view<K>(
+        &self,
+        view: K,
+    ) -> Result<Response<View>, RequestError<K::Error>>
+    where
+        K: TryInto<ViewKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = self.fav_key(view).await?;
+        self.send_fav(&key, false).await
+    }
+
     async fn maybe_fav<K>(
         &self,
         view: K,
@@ -294,11 +1240,22 @@ impl Client {
         K::Error: 'static + std::error::Error,
     {
         let key = view.try_into().context(errors::KeyError)?;
+        self.send_fav(&key, fav).await
+    }
+
+    async fn send_fav<E>(
+        &self,
+        key: &FavKey,
+        fav: bool,
+    ) -> Result<Response<View>, RequestError<E>>
+    where
+        E: 'static + std::error::Error,
+    {
         let txt = format!("https://www.furaffinity.net/{}", key.suffix(fav));
         let url = Url::parse(&txt).unwrap();
 
-        let response = self.client.read().await.get(url).send().await?;
-        Response::from_response(response).await
+        let response = self.send(self.get(url)).await?;
+        Response::from_response(response, self.config.require_auth).await
     }
 
     pub async fn others(
@@ -306,8 +1263,8 @@ impl Client {
     ) -> Result<Response<Others>, RequestError<Infallible>> {
         let url = Url::parse("https://www.furaffinity.net/msg/others").unwrap();
 
-        let response = self.client.read().await.get(url.clone()).send().await?;
-        Response::from_response(response).await
+        let response = self.send(self.get(url.clone())).await?;
+        Response::from_response(response, self.config.require_auth).await
     }
 
     pub async fn submissions<K>(
@@ -321,8 +1278,54 @@ impl Client {
         let key = key.try_into().context(errors::KeyError)?;
         let url = Url::from(key);
 
-        let response = self.client.read().await.get(url.clone()).send().await?;
-        Response::from_response(response).await
+        let response = self.send(self.get(url.clone())).await?;
+        Response::from_response(response, self.config.require_auth).await
+    }
+
+    pub async fn browse<K>(
+        &self,
+        key: K,
+    ) -> Result<Response<Browse>, RequestError<K::Error>>
+    where
+        K: TryInto<BrowseKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = key.try_into().context(errors::KeyError)?;
+        let url = Url::from(&key);
+
+        let response = self.send(self.post(url).form(&key.form())).await?;
+        Response::from_response(response, self.config.require_auth).await
+    }
+
+    pub async fn favorites<K>(
+        &self,
+        key: K,
+    ) -> Result<Response<Favorites>, RequestError<K::Error>>
+    where
+        K: TryInto<FavoritesKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = key.try_into().context(errors::KeyError)?;
+        let url = Url::from(key);
+
+        let response = self.send(self.get(url)).await?;
+        Response::from_response(response, self.config.require_auth).await
+    }
+
+    /// One page of a gallery folder. Follow [`Folder::next`] for the rest.
+    pub async fn folder<K>(
+        &self,
+        key: K,
+    ) -> Result<Response<Folder>, RequestError<K::Error>>
+    where
+        K: TryInto<FolderKey>,
+        K::Error: 'static + std::error::Error,
+    {
+        let key = key.try_into().context(errors::KeyError)?;
+        let url = Url::from(key);
+
+        let response = self.send(self.get(url)).await?;
+        Response::from_response(response, self.config.require_auth).await
     }
 
     pub async fn clear_submissions<K, I>(
@@ -347,21 +1350,9 @@ impl Client {
         let url =
             Url::parse("https://www.furaffinity.net/msg/submissions/").unwrap();
 
-        let response = self
-            .client
-            .read()
-            .await
-            .post(url)
-            .form(&form)
-            .send()
-            .await?;
+        let response = self.send(self.post(url).form(&form)).await?;
 
-        ensure!(
-            response.status().is_success(),
-            errors::Unsuccessful {
-                status: response.status()
-            },
-        );
+        ensure_success(&response)?;
 
         // TODO: Check actual HTML response
 
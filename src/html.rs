@@ -4,10 +4,12 @@ use ego_tree::NodeRef;
 use htmlescape::encode_minimal;
 
 use scraper::node::{Element, Text};
-use scraper::{ElementRef, Node};
+use scraper::{ElementRef, Html, Node, Selector};
 
 use selectors::attr::CaseSensitivity;
 
+use serde::{Deserialize, Serialize};
+
 use url::Url;
 
 pub fn simplify(root: &Url, elem: ElementRef) -> String {
@@ -23,6 +25,131 @@ pub fn simplify(root: &Url, elem: ElementRef) -> String {
     output
 }
 
+/// The plain text of `html`, with whitespace collapsed and cut to at most
+/// `max_chars` characters. Line breaks and block elements separate words.
+/// Truncated text ends in an ellipsis, which counts towards the limit.
+pub fn summary(html: &str, max_chars: usize) -> String {
+    let fragment = Html::parse_fragment(html);
+
+    let mut text = String::new();
+    for edge in fragment.root_element().traverse() {
+        match edge {
+            Edge::Open(node) => match node.value() {
+                Node::Text(t) => text.push_str(t),
+                Node::Element(e) if breaks_words(e) => text.push(' '),
+                _ => (),
+            },
+            Edge::Close(node) => match node.value() {
+                Node::Element(e) if breaks_words(e) => text.push(' '),
+                _ => (),
+            },
+        }
+    }
+
+    let words = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if words.chars().count() <= max_chars {
+        return words;
+    }
+
+    if max_chars == 0 {
+        return String::new();
+    }
+
+    let mut cut = words.chars().take(max_chars - 1).collect::<String>();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+/// Elements that separate the words on either side of them.
+const WORD_BREAKS: &[&str] = &[
+    "br",
+    "hr",
+    "p",
+    "div",
+    "blockquote",
+    "pre",
+    "ul",
+    "ol",
+    "li",
+    "table",
+    "tr",
+    "td",
+    "th",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+];
+
+fn breaks_words(elem: &Element) -> bool {
+    WORD_BREAKS.contains(&elem.name())
+}
+
+/// A `[quote]` block, with the attribution split out of the quoted text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quote {
+    author: Option<String>,
+    body: String,
+}
+
+impl Quote {
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// The quoted text, simplified the same way as [`simplify`].
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+}
+
+/// Every quote inside `elem`, in document order. Nested quotes are returned
+/// on their own and also remain part of their parent's body.
+pub fn quotes(root: &Url, elem: ElementRef) -> Vec<Quote> {
+    let selector = Selector::parse(".bbcode_quote").unwrap();
+    elem.select(&selector).map(|q| quote(root, q)).collect()
+}
+
+fn quote(root: &Url, elem: ElementRef) -> Quote {
+    let mut author = None;
+    let mut body = String::new();
+
+    for child in elem.children() {
+        if let Some(child_elem) = ElementRef::wrap(child) {
+            let is_name = child_elem.value().has_class(
+                "bbcode_quote_name",
+                CaseSensitivity::AsciiCaseInsensitive,
+            );
+
+            if is_name && author.is_none() {
+                let name = child_elem.text().collect::<String>();
+                let name = name.trim();
+                let name = name.strip_suffix("wrote:").unwrap_or(name).trim();
+                if !name.is_empty() {
+                    author = Some(name.to_string());
+                }
+                continue;
+            }
+        }
+
+        for edge in child.traverse() {
+            match edge {
+                Edge::Open(node) => simplify_open(root, &mut body, node),
+                Edge::Close(node) => simplify_close(&mut body, node),
+            }
+        }
+    }
+
+    Quote {
+        author,
+        body: body.trim().to_string(),
+    }
+}
+
 fn simplify_open(root: &Url, output: &mut String, node: NodeRef<Node>) {
     match node.value() {
         Node::Comment(_) => (),
@@ -54,7 +181,7 @@ fn bbcode_img(root: &Url, output: &mut String, elem: &Element) {
         // TODO: Get alt text
         // TODO: Don't break non-avatar images
         // TODO: Qt can't handle escaped entities in rich text...
-        let attr = encode_minimal(&src.to_string());
+        let attr = encode_minimal(src.as_str());
         let tag = format!(
             r#"<img width="50" height="50" align="middle" src="{}">"#,
             attr
@@ -67,7 +194,7 @@ fn bbcode_open_a(root: &Url, output: &mut String, elem: &Element) {
     match elem.attr("href").and_then(|h| root.join(h).ok()) {
         Some(url) => {
             // TODO: Qt can't handle escaped entities in rich text...
-            let attr = encode_minimal(&url.to_string());
+            let attr = encode_minimal(url.as_str());
             let tag = format!(r#"<a href="{}">"#, attr);
             output.push_str(&tag);
         }
@@ -135,20 +262,27 @@ fn bbcode_open(output: &mut String, elem: &Element) {
 }
 
 fn bbcode_span_color(elem: &Element) -> Option<&str> {
-    let style = match elem.attr("style") {
-        Some(s) => s,
-        None => return None,
-    };
+    let from_style = elem.attr("style").and_then(|style| {
+        style.split(';').find_map(|decl| {
+            let (property, value) = decl.split_once(':')?;
+            if property.trim().eq_ignore_ascii_case("color") {
+                Some(value.trim())
+            } else {
+                None
+            }
+        })
+    });
 
-    if !style.starts_with("color: ") {
-        return None;
+    if let Some(color) = from_style.filter(|c| !c.is_empty()) {
+        return Some(color);
     }
 
-    if !style.ends_with(';') {
-        return None;
+    // Newer posts may carry the color on the `bbcode_color` element itself.
+    if elem.has_class("bbcode_color", CaseSensitivity::AsciiCaseInsensitive) {
+        return elem.attr("data-color").filter(|c| !c.is_empty());
     }
 
-    Some(&style[7..style.len() - 1])
+    None
 }
 
 fn simplify_open_text(output: &mut String, text: &Text) {
@@ -193,9 +327,15 @@ mod tests {
                 <div id="right"><code class="bbcode bbcode_right">right</code></div>
                 <div id="center"><code class="bbcode bbcode_center">center</code></div>
                 <div id="quote"><span class="bbcode bbcode_quote"><span class="bbcode_quote_name">name</span>content</span></div>
+                <div id="quote-wrote"><span class="bbcode bbcode_quote"><span class="bbcode_quote_name">name wrote:</span> <strong class="bbcode bbcode_b">bold</strong> text</span></div>
+                <div id="quote-anon"><span class="bbcode bbcode_quote">just text</span></div>
+                <div id="quote-nested"><span class="bbcode bbcode_quote"><span class="bbcode_quote_name">outer</span><span class="bbcode bbcode_quote"><span class="bbcode_quote_name">inner</span>deep</span>shallow</span></div>
                 <div id="rule"><hr class="bbcode bbcode_hr"></div>
                 <div id="anchor"><a href="/view/1/&quot;">anchor</a></div>
                 <div id="color"><span class="bbcode" style="color: red;">red</span></div>
+                <div id="color-class"><span class="bbcode bbcode_color" style="color:#abc;">teal</span></div>
+                <div id="color-data"><span class="bbcode bbcode_color" data-color="green">green</span></div>
+                <div id="color-background"><span class="bbcode" style="background-color: red;">plain</span></div>
                 <div id="color-hex"><span class="bbcode" style="color: #0000FF;">blue</span></div>
             </body>
         </html>
@@ -273,6 +413,67 @@ mod tests {
         assert_eq!(actual, exp);
     }
 
+    fn do_quotes(selector: &str) -> Vec<Quote> {
+        let html = html();
+        let selector = Selector::parse(selector).unwrap();
+        let elem = html.select(&selector).next().unwrap();
+        let root = Url::parse("https://www.furaffinity.net/view/1/").unwrap();
+        quotes(&root, elem)
+    }
+
+    #[test]
+    fn quotes_author() {
+        let actual = do_quotes("#quote-wrote");
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].author(), Some("name"));
+        assert_eq!(actual[0].body(), "<strong>bold</strong> text");
+    }
+
+    #[test]
+    fn quotes_anonymous() {
+        let actual = do_quotes("#quote-anon");
+        assert_eq!(actual.len(), 1);
+        assert_eq!(actual[0].author(), None);
+        assert_eq!(actual[0].body(), "just text");
+    }
+
+    #[test]
+    fn quotes_nested() {
+        let actual = do_quotes("#quote-nested");
+        assert_eq!(actual.len(), 2);
+        assert_eq!(actual[0].author(), Some("outer"));
+        assert_eq!(
+            actual[0].body(),
+            r#"<blockquote class="quote"><strong class="quote-name">inner</strong>deep</blockquote>shallow"#
+        );
+        assert_eq!(actual[1].author(), Some("inner"));
+        assert_eq!(actual[1].body(), "deep");
+    }
+
+    #[test]
+    fn summary_short() {
+        assert_eq!(summary("<strong>hi</strong>  there", 20), "hi there");
+    }
+
+    #[test]
+    fn summary_truncates_text_not_markup() {
+        let actual = summary(r#"<a href="/view/1/">fish</a> &amp; chips"#, 8);
+        assert_eq!(actual, "fish &…");
+    }
+
+    #[test]
+    fn summary_separates_lines_and_blocks() {
+        assert_eq!(summary("a<br>b<br/>c", 20), "a b c");
+        assert_eq!(summary("<p>one</p><p>two</p>", 20), "one two");
+        assert_eq!(summary("<div>x</div>y<strong>z</strong>", 20), "x yz");
+    }
+
+    #[test]
+    fn summary_multibyte() {
+        assert_eq!(summary("ééééé", 4), "ééé…");
+        assert_eq!(summary("ééééé", 0), "");
+    }
+
     #[test]
     fn simplify_rule() {
         let actual = do_simplify("#rule");
@@ -294,6 +495,26 @@ mod tests {
         assert_eq!(actual, exp);
     }
 
+    #[test]
+    fn simplify_color_class() {
+        let actual = do_simplify("#color-class");
+        let exp = r##"<font color="#abc">teal</font>"##;
+        assert_eq!(actual, exp);
+    }
+
+    #[test]
+    fn simplify_color_data() {
+        let actual = do_simplify("#color-data");
+        let exp = r#"<font color="green">green</font>"#;
+        assert_eq!(actual, exp);
+    }
+
+    #[test]
+    fn simplify_background_color_ignored() {
+        let actual = do_simplify("#color-background");
+        assert_eq!(actual, "plain");
+    }
+
     #[test]
     fn simplify_color_hex() {
         let actual = do_simplify("#color-hex");
@@ -20,26 +20,35 @@ mod errors {
 }
 
 use crate::resources::msg::submissions::Order;
+use crate::resources::Rating;
 
 pub use self::errors::{FromStrError, FromUrlError};
 
+use percent_encoding::percent_decode_str;
+
+use serde::{Deserialize, Serialize};
+
 use snafu::{ensure, OptionExt, ResultExt};
 
 use std::convert::{TryFrom, TryInto};
 
 use url::Url;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct SubmissionsKey {
     order: Order,
     after: Option<u64>,
+    per_page: u16,
 }
 
 impl SubmissionsKey {
+    const DEFAULT_PER_PAGE: u16 = 72;
+
     pub fn newest() -> Self {
         Self {
             order: Order::Descending,
             after: None,
+            per_page: Self::DEFAULT_PER_PAGE,
         }
     }
 
@@ -47,13 +56,23 @@ impl SubmissionsKey {
         Self {
             order: Order::Ascending,
             after: None,
+            per_page: Self::DEFAULT_PER_PAGE,
         }
     }
+
+    pub fn per_page(&self) -> u16 {
+        self.per_page
+    }
+
+    pub fn with_per_page(mut self, per_page: u16) -> Self {
+        self.per_page = per_page;
+        self
+    }
 }
 
 impl PartialOrd for SubmissionsKey {
     fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
-        if self.order != rhs.order {
+        if self.order != rhs.order || self.per_page != rhs.per_page {
             return None;
         }
 
@@ -69,6 +88,9 @@ impl PartialOrd for SubmissionsKey {
     }
 }
 
+/// The oldest page of the inbox, like a bare `/msg/submissions/` URL. Note
+/// that this is the opposite of [`Order::default`]; use
+/// [`SubmissionsKey::newest`] for newest first.
 impl Default for SubmissionsKey {
     fn default() -> Self {
         Self::oldest()
@@ -92,12 +114,17 @@ impl TryFrom<&Url> for SubmissionsKey {
         ensure!(path.next() == Some("submissions"), errors::MissingSegment);
 
         let segment = match path.next() {
-            Some(t) => t,
-            None => return Ok(Self::default()),
+            Some(t) if !t.is_empty() => t,
+            _ => return Ok(Self::default()),
         };
 
-        let order_id =
-            segment.split('@').next().context(errors::MissingSegment)?;
+        let mut halves = segment.split('@');
+        let order_id = halves.next().context(errors::MissingSegment)?;
+
+        let per_page = match halves.next() {
+            None => Self::DEFAULT_PER_PAGE,
+            Some(x) => x.parse()?,
+        };
 
         let mut parts = order_id.split('~');
         let order_txt = parts.next().context(errors::MissingSegment)?;
@@ -113,7 +140,11 @@ impl TryFrom<&Url> for SubmissionsKey {
             Some(x) => Some(x.parse()?),
         };
 
-        Ok(Self { order, after })
+        Ok(Self {
+            order,
+            after,
+            per_page,
+        })
     }
 }
 
@@ -121,9 +152,10 @@ impl From<&SubmissionsKey> for Url {
     fn from(k: &SubmissionsKey) -> Url {
         let after = k.after.map(|id| format!("~{}", id)).unwrap_or_default();
         let text = format!(
-            "https://www.furaffinity.net/msg/submissions/{}{}@72/",
+            "https://www.furaffinity.net/msg/submissions/{}{}@{}/",
             k.order.text(),
             after,
+            k.per_page,
         );
         Url::parse(&text).unwrap()
     }
@@ -135,7 +167,191 @@ impl From<SubmissionsKey> for Url {
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct BrowseKey {
+    page: u64,
+    category: u16,
+    general: bool,
+    mature: bool,
+    adult: bool,
+}
+
+impl BrowseKey {
+    const ALL_CATEGORIES: u16 = 1;
+
+    pub fn page(&self) -> u64 {
+        self.page
+    }
+
+    /// Pages start at 1, so 0 is treated as 1.
+    pub fn with_page(mut self, page: u64) -> Self {
+        self.page = page.max(1);
+        self
+    }
+
+    /// The following page, with the same filters.
+    pub fn next(&self) -> Self {
+        self.with_page(self.page + 1)
+    }
+
+    /// The preceding page, with the same filters, or `None` on the first.
+    pub fn prev(&self) -> Option<Self> {
+        match self.page {
+            0 | 1 => None,
+            p => Some(self.with_page(p - 1)),
+        }
+    }
+
+    /// FurAffinity's numeric category id, where `1` means all categories.
+    pub fn category(&self) -> u16 {
+        self.category
+    }
+
+    pub fn with_category(mut self, category: u16) -> Self {
+        self.category = category;
+        self
+    }
+
+    pub fn includes(&self, rating: Rating) -> bool {
+        match rating {
+            Rating::General => self.general,
+            Rating::Mature => self.mature,
+            Rating::Adult => self.adult,
+        }
+    }
+
+    pub fn with_rating(mut self, rating: Rating, include: bool) -> Self {
+        match rating {
+            Rating::General => self.general = include,
+            Rating::Mature => self.mature = include,
+            Rating::Adult => self.adult = include,
+        }
+        self
+    }
+
+    pub(crate) fn form(&self) -> Vec<(&'static str, String)> {
+        let mut form = vec![
+            ("cat", self.category.to_string()),
+            ("page", self.page.to_string()),
+            ("go", "Update".to_string()),
+        ];
+
+        let ratings = [
+            ("rating_general", self.general),
+            ("rating_mature", self.mature),
+            ("rating_adult", self.adult),
+        ];
+
+        for (name, include) in ratings.iter() {
+            if *include {
+                form.push((name, "1".to_string()));
+            }
+        }
+
+        form
+    }
+}
+
+/// The first page of every category, including all ratings.
+impl Default for BrowseKey {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            category: Self::ALL_CATEGORIES,
+            general: true,
+            mature: true,
+            adult: true,
+        }
+    }
+}
+
+impl TryFrom<Url> for BrowseKey {
+    type Error = FromUrlError;
+
+    fn try_from(u: Url) -> Result<Self, Self::Error> {
+        TryFrom::try_from(&u)
+    }
+}
+
+impl TryFrom<&Url> for BrowseKey {
+    type Error = FromUrlError;
+
+    fn try_from(u: &Url) -> Result<Self, Self::Error> {
+        let mut path = u.path_segments().context(errors::MissingSegment)?;
+        ensure!(path.next() == Some("browse"), errors::MissingSegment);
+
+        let page = match path.next() {
+            Some(t) if !t.is_empty() => t.parse()?,
+            _ => 1,
+        };
+
+        let mut key = Self::default().with_page(page);
+
+        // Once any rating is in the query, a missing one means it's
+        // excluded, like in the form. Other parameters leave them alone.
+        let has_rating = u.query_pairs().any(|(name, _)| {
+            matches!(
+                name.as_ref(),
+                "rating_general" | "rating_mature" | "rating_adult"
+            )
+        });
+        if has_rating {
+            key.general = false;
+            key.mature = false;
+            key.adult = false;
+        }
+
+        for (name, value) in u.query_pairs() {
+            match name.as_ref() {
+                "cat" => key.category = value.parse()?,
+                "rating_general" => key.general = true,
+                "rating_mature" => key.mature = true,
+                "rating_adult" => key.adult = true,
+                _ => (),
+            }
+        }
+
+        Ok(key)
+    }
+}
+
+impl TryFrom<&str> for BrowseKey {
+    type Error = FromStrError;
+
+    fn try_from(txt: &str) -> Result<Self, Self::Error> {
+        let url = Url::parse(txt).context(errors::MalformedUrl)?;
+        url.try_into().context(errors::FromUrl)
+    }
+}
+
+/// The filters are carried in the query, so they survive a round trip
+/// through the URL. The one exception is a key that excludes every rating,
+/// which the form has no way to say, so it comes back including all of them.
+impl From<&BrowseKey> for Url {
+    fn from(k: &BrowseKey) -> Url {
+        let txt = format!("https://www.furaffinity.net/browse/{}/", k.page);
+        let mut url = Url::parse(&txt).unwrap();
+
+        let default = BrowseKey::default().with_page(k.page);
+        if *k != default {
+            url.query_pairs_mut().extend_pairs(
+                k.form()
+                    .iter()
+                    .filter(|(name, _)| *name != "page" && *name != "go"),
+            );
+        }
+
+        url
+    }
+}
+
+impl From<BrowseKey> for Url {
+    fn from(k: BrowseKey) -> Url {
+        From::from(&k)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct FavKey {
     view_id: u64,
     key: String,
@@ -314,6 +530,40 @@ impl CommentReplyKey {
             reply_to: ReplyTo::JournalComment(cid),
         }
     }
+
+    pub(crate) fn comment_id(&self) -> Option<u64> {
+        match self.reply_to {
+            ReplyTo::ViewComment(cid) | ReplyTo::JournalComment(cid) => {
+                Some(cid)
+            }
+            ReplyTo::View(_) | ReplyTo::Journal(_) => None,
+        }
+    }
+
+    fn comment_action_url(&self, action: &str) -> Option<Url> {
+        let root = match self.reply_to {
+            ReplyTo::ViewComment(_) => "submission",
+            ReplyTo::JournalComment(_) => "journal",
+            ReplyTo::View(_) | ReplyTo::Journal(_) => return None,
+        };
+
+        let txt = format!(
+            "https://www.furaffinity.net/{}/{}/{}/",
+            action,
+            root,
+            self.comment_id()?
+        );
+
+        Some(Url::parse(&txt).unwrap())
+    }
+
+    pub(crate) fn edit_url(&self) -> Option<Url> {
+        self.comment_action_url("edit")
+    }
+
+    pub(crate) fn delete_url(&self) -> Option<Url> {
+        self.comment_action_url("delete")
+    }
 }
 
 impl TryFrom<&str> for CommentReplyKey {
@@ -401,7 +651,388 @@ impl From<JournalKey> for Url {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+/// Defaults to an empty query.
+#[derive(
+    Debug, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
+pub struct SearchKey {
+    pub query: String,
+}
+
+impl SearchKey {
+    /// Search for submissions tagged with `keyword`.
+    pub fn keyword(keyword: &str) -> Self {
+        Self {
+            query: format!("@keywords {}", keyword),
+        }
+    }
+}
+
+impl TryFrom<Url> for SearchKey {
+    type Error = FromUrlError;
+
+    fn try_from(url: Url) -> Result<Self, Self::Error> {
+        TryFrom::try_from(&url)
+    }
+}
+
+impl TryFrom<&Url> for SearchKey {
+    type Error = FromUrlError;
+
+    fn try_from(url: &Url) -> Result<Self, Self::Error> {
+        let mut segments =
+            url.path_segments().context(errors::MissingSegment)?;
+
+        ensure!(segments.next() == Some("search"), errors::MissingSegment);
+
+        let query = match segments.next() {
+            Some(q) if !q.is_empty() => {
+                percent_decode_str(q).decode_utf8_lossy().into_owned()
+            }
+            _ => url
+                .query_pairs()
+                .find(|(k, _)| k == "q")
+                .map(|(_, v)| v.into_owned())
+                .context(errors::MissingSegment)?,
+        };
+
+        Ok(SearchKey { query })
+    }
+}
+
+impl TryFrom<&str> for SearchKey {
+    type Error = FromStrError;
+
+    fn try_from(txt: &str) -> Result<Self, Self::Error> {
+        let url = Url::parse(txt).context(errors::MalformedUrl)?;
+        url.try_into().context(errors::FromUrl)
+    }
+}
+
+impl From<&SearchKey> for Url {
+    fn from(key: &SearchKey) -> Url {
+        let mut url =
+            Url::parse("https://www.furaffinity.net/search/").unwrap();
+        url.query_pairs_mut().append_pair("q", &key.query);
+        url
+    }
+}
+
+impl From<SearchKey> for Url {
+    fn from(key: SearchKey) -> Url {
+        From::from(&key)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub struct UserKey {
+    pub slug: String,
+}
+
+impl UserKey {
+    /// Slugs are case-insensitive on FurAffinity, so they're always kept in
+    /// lowercase.
+    pub fn new<S>(slug: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            slug: slug.into().to_lowercase(),
+        }
+    }
+}
+
+impl AsRef<str> for UserKey {
+    fn as_ref(&self) -> &str {
+        &self.slug
+    }
+}
+
+impl From<UserKey> for String {
+    fn from(key: UserKey) -> String {
+        key.slug
+    }
+}
+
+impl TryFrom<Url> for UserKey {
+    type Error = FromUrlError;
+
+    fn try_from(url: Url) -> Result<Self, Self::Error> {
+        TryFrom::try_from(&url)
+    }
+}
+
+impl TryFrom<&Url> for UserKey {
+    type Error = FromUrlError;
+
+    fn try_from(url: &Url) -> Result<Self, Self::Error> {
+        let mut segments =
+            url.path_segments().context(errors::MissingSegment)?;
+
+        ensure!(segments.next() == Some("user"), errors::MissingSegment);
+
+        let slug = segments.next().context(errors::MissingSegment)?;
+        ensure!(!slug.is_empty(), errors::MissingSegment);
+
+        Ok(UserKey::new(slug))
+    }
+}
+
+impl TryFrom<&str> for UserKey {
+    type Error = FromStrError;
+
+    fn try_from(txt: &str) -> Result<Self, Self::Error> {
+        let url = Url::parse(txt).context(errors::MalformedUrl)?;
+        url.try_into().context(errors::FromUrl)
+    }
+}
+
+impl From<&UserKey> for Url {
+    fn from(key: &UserKey) -> Url {
+        let txt = format!("https://www.furaffinity.net/user/{}/", key.slug);
+        Url::parse(&txt).unwrap()
+    }
+}
+
+impl From<UserKey> for Url {
+    fn from(key: UserKey) -> Url {
+        From::from(&key)
+    }
+}
+
+/// Position within a user's favorites, as found in the "Next" and "Prev"
+/// links of a favorites page.
+///
+/// FurAffinity encodes a favorite id and a direction into these, so a cursor
+/// is only meaningful when it was taken from a real page. There is
+/// deliberately no way to build one by hand.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FavoritesCursor(String);
+
+impl FavoritesCursor {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct FavoritesKey {
+    slug: String,
+    cursor: Option<FavoritesCursor>,
+}
+
+impl FavoritesKey {
+    pub fn user(&self) -> UserKey {
+        UserKey::new(self.slug.as_str())
+    }
+
+    /// `None` for the first page.
+    pub fn cursor(&self) -> Option<&FavoritesCursor> {
+        self.cursor.as_ref()
+    }
+}
+
+impl From<UserKey> for FavoritesKey {
+    fn from(user: UserKey) -> Self {
+        Self {
+            slug: user.slug.to_lowercase(),
+            cursor: None,
+        }
+    }
+}
+
+impl From<&UserKey> for FavoritesKey {
+    fn from(user: &UserKey) -> Self {
+        From::from(user.clone())
+    }
+}
+
+impl TryFrom<Url> for FavoritesKey {
+    type Error = FromUrlError;
+
+    fn try_from(url: Url) -> Result<Self, Self::Error> {
+        TryFrom::try_from(&url)
+    }
+}
+
+impl TryFrom<&Url> for FavoritesKey {
+    type Error = FromUrlError;
+
+    fn try_from(url: &Url) -> Result<Self, Self::Error> {
+        // Everything after the slug is kept verbatim, so that the cursor
+        // round-trips exactly.
+        let mut parts = url.path().splitn(4, '/');
+        ensure!(parts.next() == Some(""), errors::MissingSegment);
+        ensure!(parts.next() == Some("favorites"), errors::MissingSegment);
+
+        let slug = parts.next().context(errors::MissingSegment)?;
+        ensure!(!slug.is_empty(), errors::MissingSegment);
+
+        let mut cursor = parts.next().unwrap_or_default().to_string();
+        if let Some(query) = url.query() {
+            cursor.push('?');
+            cursor.push_str(query);
+        }
+
+        let cursor = if cursor.is_empty() {
+            None
+        } else {
+            Some(FavoritesCursor(cursor))
+        };
+
+        Ok(FavoritesKey {
+            slug: slug.to_lowercase(),
+            cursor,
+        })
+    }
+}
+
+impl TryFrom<&str> for FavoritesKey {
+    type Error = FromStrError;
+
+    fn try_from(txt: &str) -> Result<Self, Self::Error> {
+        let url = Url::parse(txt).context(errors::MalformedUrl)?;
+        url.try_into().context(errors::FromUrl)
+    }
+}
+
+impl From<&FavoritesKey> for Url {
+    fn from(key: &FavoritesKey) -> Url {
+        let cursor = key.cursor.as_ref().map(FavoritesCursor::as_str);
+        let cursor = cursor.unwrap_or_default();
+        let txt = format!(
+            "https://www.furaffinity.net/favorites/{}/{}",
+            key.slug, cursor
+        );
+        Url::parse(&txt).unwrap()
+    }
+}
+
+impl From<FavoritesKey> for Url {
+    fn from(key: FavoritesKey) -> Url {
+        From::from(&key)
+    }
+}
+
+/// A page of one of a user's gallery folders, like
+/// `/gallery/<user>/folder/<folder_id>/<name_slug>/`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct FolderKey {
+    pub user: String,
+    pub folder_id: u64,
+    /// The folder's name as it appears in the URL. FurAffinity doesn't check
+    /// it, but it's kept so the URL round-trips.
+    pub name_slug: String,
+    page: u64,
+}
+
+impl FolderKey {
+    pub fn new<U, N>(user: U, folder_id: u64, name_slug: N) -> Self
+    where
+        U: Into<String>,
+        N: Into<String>,
+    {
+        Self {
+            user: user.into().to_lowercase(),
+            folder_id,
+            name_slug: name_slug.into(),
+            page: 1,
+        }
+    }
+
+    pub fn user_key(&self) -> UserKey {
+        UserKey::new(self.user.as_str())
+    }
+
+    /// Starts at one.
+    pub fn page(&self) -> u64 {
+        self.page
+    }
+
+    pub fn with_page(mut self, page: u64) -> Self {
+        self.page = page.max(1);
+        self
+    }
+}
+
+impl TryFrom<Url> for FolderKey {
+    type Error = FromUrlError;
+
+    fn try_from(url: Url) -> Result<Self, Self::Error> {
+        TryFrom::try_from(&url)
+    }
+}
+
+impl TryFrom<&Url> for FolderKey {
+    type Error = FromUrlError;
+
+    fn try_from(url: &Url) -> Result<Self, Self::Error> {
+        let mut path = url.path_segments().context(errors::MissingSegment)?;
+        ensure!(path.next() == Some("gallery"), errors::MissingSegment);
+
+        let user = path.next().context(errors::MissingSegment)?;
+        ensure!(!user.is_empty(), errors::MissingSegment);
+
+        ensure!(path.next() == Some("folder"), errors::MissingSegment);
+        let folder_id = path.next().context(errors::MissingSegment)?.parse()?;
+        let name_slug = path.next().unwrap_or_default();
+
+        let page = match path.next() {
+            None | Some("") => 1,
+            Some(p) => p.parse()?,
+        };
+
+        Ok(Self::new(user, folder_id, name_slug).with_page(page))
+    }
+}
+
+impl TryFrom<&str> for FolderKey {
+    type Error = FromStrError;
+
+    fn try_from(txt: &str) -> Result<Self, Self::Error> {
+        let url = Url::parse(txt).context(errors::MalformedUrl)?;
+        url.try_into().context(errors::FromUrl)
+    }
+}
+
+impl From<&FolderKey> for Url {
+    fn from(key: &FolderKey) -> Url {
+        let mut txt = format!(
+            "https://www.furaffinity.net/gallery/{}/folder/{}/",
+            key.user, key.folder_id
+        );
+
+        if !key.name_slug.is_empty() {
+            txt.push_str(&key.name_slug);
+            txt.push('/');
+        }
+
+        if key.page > 1 {
+            txt.push_str(&format!("{}/", key.page));
+        }
+
+        Url::parse(&txt).unwrap()
+    }
+}
+
+impl From<FolderKey> for Url {
+    fn from(key: FolderKey) -> Url {
+        From::from(&key)
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
 pub struct ViewKey {
     pub view_id: u64,
 }
@@ -455,11 +1086,13 @@ mod tests {
         let none = SubmissionsKey {
             order: Order::Descending,
             after: None,
+            per_page: 72,
         };
 
         let some = SubmissionsKey {
             order: Order::Descending,
             after: Some(1),
+            per_page: 72,
         };
 
         assert!(none < some);
@@ -471,11 +1104,13 @@ mod tests {
         let none = SubmissionsKey {
             order: Order::Ascending,
             after: None,
+            per_page: 72,
         };
 
         let some = SubmissionsKey {
             order: Order::Ascending,
             after: Some(1),
+            per_page: 72,
         };
 
         assert!(none < some);
@@ -487,11 +1122,13 @@ mod tests {
         let less = SubmissionsKey {
             order: Order::Ascending,
             after: Some(1),
+            per_page: 72,
         };
 
         let more = SubmissionsKey {
             order: Order::Ascending,
             after: Some(2),
+            per_page: 72,
         };
 
         assert!(less < more);
@@ -503,11 +1140,13 @@ mod tests {
         let less = SubmissionsKey {
             order: Order::Descending,
             after: Some(2),
+            per_page: 72,
         };
 
         let more = SubmissionsKey {
             order: Order::Descending,
             after: Some(1),
+            per_page: 72,
         };
 
         assert!(less < more);
@@ -525,6 +1164,7 @@ mod tests {
         let expected = SubmissionsKey {
             order: Order::Descending,
             after: Some(38549204),
+            per_page: 48,
         };
 
         assert_eq!(actual, expected);
@@ -540,6 +1180,7 @@ mod tests {
         let expected = SubmissionsKey {
             order: Order::Descending,
             after: None,
+            per_page: 48,
         };
 
         assert_eq!(actual, expected);
@@ -555,11 +1196,41 @@ mod tests {
         let expected = SubmissionsKey {
             order: Order::Ascending,
             after: None,
+            per_page: 48,
         };
 
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn submissions_key_from_bare_url() {
+        let url =
+            Url::parse("https://www.furaffinity.net/msg/submissions/").unwrap();
+
+        let actual = SubmissionsKey::try_from(url).unwrap();
+        assert_eq!(actual, SubmissionsKey::default());
+    }
+
+    #[test]
+    fn submissions_key_round_trip() {
+        for order in [Order::Ascending, Order::Descending].iter().copied() {
+            for after in [None, Some(38549204)].iter().copied() {
+                for per_page in [24, 48, 72].iter().copied() {
+                    let key = SubmissionsKey {
+                        order,
+                        after,
+                        per_page,
+                    };
+
+                    let url = Url::from(&key);
+                    let parsed = SubmissionsKey::try_from(&url).unwrap();
+                    assert_eq!(parsed, key);
+                    assert_eq!(Url::from(&parsed), url);
+                }
+            }
+        }
+    }
+
     #[test]
     fn comment_reply_key_from_url_view_journal() {
         let url = Url::parse(
@@ -645,4 +1316,207 @@ mod tests {
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn browse_key_from_url() {
+        let url = Url::parse("https://www.furaffinity.net/browse/3/").unwrap();
+        let key = BrowseKey::try_from(url).unwrap();
+        assert_eq!(key, BrowseKey::default().with_page(3));
+
+        let url = Url::parse("https://www.furaffinity.net/browse/").unwrap();
+        let key = BrowseKey::try_from(url).unwrap();
+        assert_eq!(key, BrowseKey::default());
+
+        let url = Url::parse("https://www.furaffinity.net/browse/0/").unwrap();
+        let key = BrowseKey::try_from(url).unwrap();
+        assert_eq!(key.page(), 1);
+    }
+
+    #[test]
+    fn browse_key_filters_round_trip() {
+        let key = BrowseKey::default()
+            .with_page(2)
+            .with_category(2)
+            .with_rating(Rating::Adult, false);
+
+        let url = Url::from(&key);
+        assert_eq!(
+            url.as_str(),
+            "https://www.furaffinity.net/browse/2/\
+             ?cat=2&rating_general=1&rating_mature=1"
+        );
+        assert_eq!(BrowseKey::try_from(url).unwrap(), key);
+
+        let plain = Url::from(BrowseKey::default());
+        assert_eq!(plain.as_str(), "https://www.furaffinity.net/browse/1/");
+    }
+
+    #[test]
+    fn browse_key_ignores_unrelated_query() {
+        let url = "https://www.furaffinity.net/browse/3/?utm_source=feed";
+        let key = BrowseKey::try_from(url).unwrap();
+        assert_eq!(key, BrowseKey::default().with_page(3));
+
+        let url = "https://www.furaffinity.net/browse/?cat=2&ref=x";
+        let key = BrowseKey::try_from(url).unwrap();
+        assert_eq!(key, BrowseKey::default().with_category(2));
+
+        let url = "https://www.furaffinity.net/browse/?rating_general=1&ref=x";
+        let key = BrowseKey::try_from(url).unwrap();
+        assert!(key.includes(Rating::General));
+        assert!(!key.includes(Rating::Mature));
+        assert!(!key.includes(Rating::Adult));
+    }
+
+    #[test]
+    fn browse_key_next_prev() {
+        let key = BrowseKey::default()
+            .with_category(2)
+            .with_rating(Rating::Adult, false);
+
+        let next = key.next();
+        assert_eq!(next.page(), 2);
+        assert_eq!(next.category(), 2);
+        assert!(!next.includes(Rating::Adult));
+
+        assert_eq!(next.prev(), Some(key));
+        assert_eq!(key.prev(), None);
+        assert_eq!(key.with_page(0).page(), 1);
+    }
+
+    #[test]
+    fn browse_key_form() {
+        let key = BrowseKey::default()
+            .with_category(2)
+            .with_rating(Rating::Adult, false);
+
+        let form = key.form();
+        assert!(form.contains(&("cat", "2".to_string())));
+        assert!(form.contains(&("rating_general", "1".to_string())));
+        assert!(!form.iter().any(|(name, _)| *name == "rating_adult"));
+    }
+
+    #[test]
+    fn comment_reply_key_edit_url() {
+        let key = CommentReplyKey::view_comment(57397217);
+        let expected =
+            Url::parse("https://www.furaffinity.net/edit/submission/57397217/")
+                .unwrap();
+        assert_eq!(key.edit_url(), Some(expected));
+
+        assert_eq!(CommentReplyKey::view(9573919).edit_url(), None);
+    }
+
+    #[test]
+    fn comment_reply_key_delete_url() {
+        let key = CommentReplyKey::journal_comment(57397217);
+        let expected =
+            Url::parse("https://www.furaffinity.net/delete/journal/57397217/")
+                .unwrap();
+        assert_eq!(key.delete_url(), Some(expected));
+    }
+
+    #[test]
+    fn user_key_round_trip() {
+        let key = UserKey::try_from("https://www.furaffinity.net/user/TehKey/")
+            .unwrap();
+        assert_eq!(key.slug, "tehkey");
+
+        let url = Url::from(&key);
+        assert_eq!(url.as_str(), "https://www.furaffinity.net/user/tehkey/");
+        assert_eq!(UserKey::try_from(url).unwrap(), key);
+
+        assert_eq!(key.as_ref(), "tehkey");
+        assert_eq!(String::from(key), "tehkey");
+    }
+
+    #[test]
+    fn favorites_key_round_trip() {
+        let urls = [
+            "https://www.furaffinity.net/favorites/tehkey/",
+            "https://www.furaffinity.net/favorites/tehkey/1012345601/next",
+            "https://www.furaffinity.net/favorites/tehkey/1012345678/prev/",
+            "https://www.furaffinity.net/favorites/tehkey/?page=abc",
+        ];
+
+        for txt in urls.iter() {
+            let key = FavoritesKey::try_from(*txt).unwrap();
+            assert_eq!(Url::from(&key).as_str(), *txt);
+        }
+    }
+
+    #[test]
+    fn favorites_key_first_page() {
+        let user = UserKey {
+            slug: "tehkey".into(),
+        };
+        let key = FavoritesKey::from(&user);
+        assert_eq!(key.cursor(), None);
+        assert_eq!(key.user(), user);
+
+        let url =
+            Url::parse("https://www.furaffinity.net/favorites/tehkey").unwrap();
+        assert_eq!(FavoritesKey::try_from(url).unwrap(), key);
+    }
+
+    #[test]
+    fn key_defaults() {
+        let browse = BrowseKey::default();
+        assert_eq!(browse.page(), 1);
+        assert_eq!(browse.category(), 1);
+        assert!(browse.includes(Rating::General));
+        assert!(browse.includes(Rating::Mature));
+        assert!(browse.includes(Rating::Adult));
+
+        assert_eq!(SearchKey::default().query, "");
+        assert_eq!(Order::default(), Order::Descending);
+        assert_eq!(SubmissionsKey::default(), SubmissionsKey::oldest());
+    }
+
+    #[test]
+    fn folder_key_round_trip() {
+        let urls = [
+            "https://www.furaffinity.net/gallery/tehkey/folder/12345/Adopts/",
+            "https://www.furaffinity.net/gallery/tehkey/folder/12345/Adopts/3/",
+        ];
+
+        for txt in urls.iter() {
+            let key = FolderKey::try_from(*txt).unwrap();
+            assert_eq!(Url::from(&key).as_str(), *txt);
+        }
+    }
+
+    #[test]
+    fn folder_key_parts() {
+        let key = FolderKey::try_from(
+            "https://www.furaffinity.net/gallery/TehKey/folder/12345/Adopts/2",
+        )
+        .unwrap();
+
+        assert_eq!(key.user, "tehkey");
+        assert_eq!(key.folder_id, 12345);
+        assert_eq!(key.name_slug, "Adopts");
+        assert_eq!(key.page(), 2);
+        assert_eq!(key.clone().with_page(0).page(), 1);
+
+        FolderKey::try_from("https://www.furaffinity.net/gallery/tehkey/")
+            .unwrap_err();
+        FolderKey::try_from(
+            "https://www.furaffinity.net/gallery/tehkey/folder/abc/Adopts/",
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn search_key_from_tag_href() {
+        let page =
+            Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+        let url = page.join("/search/@keywords adopt").unwrap();
+
+        let key = SearchKey::try_from(&url).unwrap();
+        assert_eq!(key, SearchKey::keyword("adopt"));
+
+        let round = SearchKey::try_from(Url::from(&key)).unwrap();
+        assert_eq!(round, key);
+    }
 }
@@ -2,3 +2,18 @@ pub mod client;
 mod html;
 pub mod keys;
 pub mod resources;
+
+use resources::{FromHtml, ParseError};
+
+use scraper::Html;
+
+use url::Url;
+
+/// Parse a saved page as `T`, like one archived from an earlier crawl. `url`
+/// is where the page was fetched from, which is used to resolve its links.
+pub fn parse<T>(url: Url, html: &str) -> Result<T, ParseError>
+where
+    T: FromHtml,
+{
+    T::from_html(url, &Html::parse_document(html))
+}
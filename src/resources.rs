@@ -15,6 +15,11 @@ mod parse_error {
             source: url::ParseError,
         },
         IncorrectUrl,
+        #[snafu(display("malformed CDN URL {}: {}", url, reason))]
+        MalformedCdnUrl {
+            url: url::Url,
+            reason: &'static str,
+        },
         #[snafu(context(false))]
         InvalidInteger {
             source: std::num::ParseIntError,
@@ -26,6 +31,13 @@ mod parse_error {
         UnknownRating {
             text: String,
         },
+        UnknownPreviewSize {
+            text: String,
+        },
+        #[snafu(display("count {:?} is too large", text))]
+        CountTooLarge {
+            text: String,
+        },
         InvalidDepth {
             style: String,
         },
@@ -33,18 +45,37 @@ mod parse_error {
         Json {
             source: serde_json::Error,
         },
+        /// `rating` is `None` when FurAffinity doesn't say which rating
+        /// caused the block.
         #[snafu(display("adult/mature content is currently blocked"))]
-        Nsfw,
+        Nsfw {
+            rating: Option<super::Rating>,
+        },
+        #[snafu(display("the requested page does not exist"))]
+        NotFound,
+        #[snafu(display("the owner's account has been disabled"))]
+        AccountDisabled,
+        #[snafu(display("the page is only available to registered users"))]
+        RegistrationRequired,
+        #[snafu(display("request was redirected to {}", to))]
+        Redirected {
+            to: url::Url,
+        },
     }
 }
 
+pub mod browse;
 pub mod comment;
+pub mod favorites;
+pub mod folder;
 pub mod header;
 pub mod journal;
 pub mod msg;
+pub(crate) mod shout;
+pub(crate) mod upload;
 pub mod view;
 
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDateTime};
 
 use regex::Regex;
 
@@ -52,9 +83,15 @@ use scraper::{ElementRef, Html, Selector};
 
 pub use self::parse_error::ParseError;
 
-use snafu::OptionExt;
+pub use crate::html::Quote;
+
+use serde::{Deserialize, Serialize};
 
+use snafu::{ensure, OptionExt};
+
+use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::str::FromStr;
 
 use url::Url;
@@ -73,6 +110,11 @@ pub trait FromHtml: Sized {
     fn from_html(url: Url, document: &Html) -> Result<Self, ParseError>;
 }
 
+/// The address a resource can be found at in a browser.
+pub trait Permalink {
+    fn permalink(&self) -> Url;
+}
+
 lazy_static::lazy_static! {
     static ref RE_DATETIME: Regex =
         Regex::new("(?:^on )|(?P<d>[0-9]+)(?:st|th|nd|rd)").unwrap();
@@ -98,7 +140,39 @@ fn datetime(elem: ElementRef) -> Result<NaiveDateTime, ParseError> {
 }
 
 fn number(elem: ElementRef) -> Result<u64, ParseError> {
-    Ok(text(elem).parse()?)
+    number_from_str(text(elem).trim())
+}
+
+/// Parses counts like `"42"`, as well as the abbreviated `"1.8k"` and
+/// `"2.3m"` FA shows for popular submissions. Abbreviated counts are only as
+/// precise as the text.
+fn number_from_str(txt: &str) -> Result<u64, ParseError> {
+    let (digits, multiplier) = match txt.char_indices().last() {
+        Some((i, 'k')) | Some((i, 'K')) => (&txt[..i], 1_000),
+        Some((i, 'm')) | Some((i, 'M')) => (&txt[..i], 1_000_000),
+        _ => return Ok(txt.parse()?),
+    };
+
+    let (whole, fraction) = match digits.find('.') {
+        Some(dot) => (&digits[..dot], &digits[dot + 1..]),
+        None => (digits, ""),
+    };
+
+    let too_large = || parse_error::CountTooLarge { text: txt };
+
+    let mut value = whole
+        .parse::<u64>()?
+        .checked_mul(multiplier)
+        .with_context(too_large)?;
+
+    let mut scale = multiplier;
+    for digit in fraction.chars() {
+        scale /= 10;
+        let digit = digit.to_string().parse::<u64>()?;
+        value = value.checked_add(digit * scale).with_context(too_large)?;
+    }
+
+    Ok(value)
 }
 
 fn text(elem: ElementRef) -> String {
@@ -114,6 +188,29 @@ fn attr<'a>(
         .context(parse_error::MissingAttribute { attribute })
 }
 
+/// The slug from a `/user/<slug>/` link. FA doesn't always include the
+/// trailing slash.
+fn user_slug(href: &str) -> Result<&str, ParseError> {
+    let slug = href
+        .strip_prefix("/user/")
+        .context(parse_error::IncorrectUrl)?;
+    Ok(slug.strip_suffix('/').unwrap_or(slug))
+}
+
+/// The hidden inputs of a form, in order, so they can be posted back.
+fn hidden_fields(
+    form: ElementRef,
+) -> Result<Vec<(String, String)>, ParseError> {
+    let input_sel = Selector::parse("input[type='hidden'][name]").unwrap();
+    form.select(&input_sel)
+        .map(|i| {
+            let name = attr(i, "name")?.to_string();
+            let value = i.value().attr("value").unwrap_or_default();
+            Ok((name, value.to_string()))
+        })
+        .collect()
+}
+
 fn select_first_elem<'a>(
     elem: ElementRef<'a>,
     css: &'static str,
@@ -136,9 +233,170 @@ fn select_first<'a>(
         .context(parse_error::MissingElement { selector: css })
 }
 
+pub(crate) fn system_message(document: &Html) -> Option<String> {
+    let sel = Selector::parse(".notice-message .section-body").unwrap();
+    document.select(&sel).map(text).next()
+}
+
+fn is_not_found(document: &Html) -> bool {
+    let sel = Selector::parse(".notice-message .section-body").unwrap();
+    document.select(&sel).map(text).any(|t| {
+        t.contains("not in our database") || t.contains("cannot be found")
+    })
+}
+
+/// Whether the page belongs to a user who deactivated their account, which
+/// hides all of their submissions and journals.
+fn is_account_disabled(document: &Html) -> bool {
+    system_message(document)
+        .map(|t| t.contains("voluntarily disabled access"))
+        .unwrap_or(false)
+}
+
+/// Whether FA showed its "registered users only" wall instead of the page,
+/// either as a system message or by redirecting to the login page.
+pub(crate) fn is_registration_required(url: &Url, document: &Html) -> bool {
+    if url.path().starts_with("/login") {
+        return true;
+    }
+
+    system_message(document)
+        .map(|t| t.contains("registered users only"))
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Deserialize)]
+struct SubInfo {
+    title: String,
+    description: String,
+    username: String,
+    lower: String,
+    avatar_mtime: String,
+}
+
+fn descriptions(doc: &Html) -> Result<HashMap<u64, SubInfo>, ParseError> {
+    let script_sel = Selector::parse("script").unwrap();
+    let script_txt = doc
+        .select(&script_sel)
+        .map(text)
+        .find(|x| x.contains("var descriptions ="))
+        .context(parse_error::MissingElement { selector: "script" })?;
+
+    let descriptions_txt = script_txt
+        .split(";\n")
+        .next()
+        .context(parse_error::MissingElement { selector: "script" })?
+        .trim();
+
+    if descriptions_txt.starts_with("var descriptions = []") {
+        return Ok(HashMap::new());
+    }
+
+    ensure!(
+        descriptions_txt.starts_with("var descriptions = {"),
+        parse_error::MissingElement { selector: "script" }
+    );
+    ensure!(
+        descriptions_txt.ends_with('}'),
+        parse_error::MissingElement { selector: "script" }
+    );
+    let descriptions_txt = &descriptions_txt[19..];
+
+    let descriptions_str: HashMap<&str, SubInfo> =
+        serde_json::from_str(descriptions_txt)?;
+
+    let mut descriptions: HashMap<u64, SubInfo> =
+        HashMap::with_capacity(descriptions_str.len());
+
+    for (sid_txt, sub_info) in descriptions_str.into_iter() {
+        let sid = sid_txt.parse()?;
+        descriptions.insert(sid, sub_info);
+    }
+
+    Ok(descriptions)
+}
+
+fn parse_figure(
+    url: &Url,
+    figure_elem: ElementRef,
+    descriptions: &mut HashMap<u64, SubInfo>,
+) -> Result<Submission, ParseError> {
+    let class = attr(figure_elem, "class")?;
+    let rating = if class.contains("r-adult") {
+        Rating::Adult
+    } else if class.contains("r-mature") {
+        Rating::Mature
+    } else if class.contains("r-general") {
+        Rating::General
+    } else {
+        return Err(ParseError::MissingAttribute { attribute: "class" });
+    };
+    let kind = if class.contains("t-image") {
+        SubmissionKind::Image
+    } else if class.contains("t-flash") {
+        SubmissionKind::Flash
+    } else if class.contains("t-audio") {
+        SubmissionKind::Audio
+    } else if class.contains("t-text") {
+        SubmissionKind::Text
+    } else {
+        return Err(ParseError::MissingAttribute { attribute: "class" });
+    };
+
+    let id_attr = attr(figure_elem, "id")?;
+    ensure!(
+        id_attr.starts_with("sid-"),
+        parse_error::MissingAttribute { attribute: "id" }
+    );
+    let view_id = id_attr[4..].parse()?;
+
+    let preview_elem = select_first_elem(figure_elem, "img")?;
+    let preview_attr = attr(preview_elem, "src")?;
+    let preview = url.join(preview_attr)?;
+    let (cdn, created) = Submission::parse_url(&preview)?;
+
+    let sub_info = descriptions
+        .remove(&view_id)
+        .context(parse_error::MissingElement { selector: "script" })?;
+
+    // TODO: sometimes it's a2.facdn.net instead.
+    let avatar = url
+        .join(&format!(
+            "//a.facdn.net/{}/{}.gif",
+            sub_info.avatar_mtime, sub_info.lower
+        ))
+        .unwrap();
+
+    Ok(Submission {
+        view_id,
+        rating,
+        cdn,
+        created,
+        kind,
+        title: sub_info.title,
+        description: sub_info.description,
+        artist: MiniUser {
+            name: sub_info.username,
+            slug: sub_info.lower,
+            avatar,
+        },
+    })
+}
+
 // TODO: Create a AsUserRef or somesuch trait that can be used to fetch a user
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+#[derive(
+    Debug,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Copy,
+    Clone,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
 pub enum Rating {
     General,
     Mature,
@@ -183,7 +441,72 @@ pub enum PreviewSize {
     Xxxl, // 600
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+impl PreviewSize {
+    pub fn pixels(self) -> u16 {
+        match self {
+            PreviewSize::Xxxl => 600,
+            PreviewSize::Xxl => 400,
+            PreviewSize::Xl => 300,
+            PreviewSize::L => 250,
+            PreviewSize::M => 200,
+            PreviewSize::S => 150,
+            PreviewSize::Xs => 120,
+            PreviewSize::Xxs => 100,
+            PreviewSize::Xxxs => 50,
+        }
+    }
+
+    pub fn from_pixels(pixels: u16) -> Option<Self> {
+        let sz = match pixels {
+            600 => PreviewSize::Xxxl,
+            400 => PreviewSize::Xxl,
+            300 => PreviewSize::Xl,
+            250 => PreviewSize::L,
+            200 => PreviewSize::M,
+            150 => PreviewSize::S,
+            120 => PreviewSize::Xs,
+            100 => PreviewSize::Xxs,
+            50 => PreviewSize::Xxxs,
+            _ => return None,
+        };
+
+        Some(sz)
+    }
+}
+
+impl FromStr for PreviewSize {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, ParseError> {
+        let sz = match text.to_ascii_lowercase().as_str() {
+            "xxxl" => Some(PreviewSize::Xxxl),
+            "xxl" => Some(PreviewSize::Xxl),
+            "xl" => Some(PreviewSize::Xl),
+            "l" => Some(PreviewSize::L),
+            "m" => Some(PreviewSize::M),
+            "s" => Some(PreviewSize::S),
+            "xs" => Some(PreviewSize::Xs),
+            "xxs" => Some(PreviewSize::Xxs),
+            "xxxs" => Some(PreviewSize::Xxxs),
+            other => other.parse().ok().and_then(Self::from_pixels),
+        };
+
+        sz.context(parse_error::UnknownPreviewSize { text })
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
 pub enum SubmissionKind {
     Image,
     Flash,
@@ -191,7 +514,7 @@ pub enum SubmissionKind {
     Audio,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Submission {
     view_id: u64,
     created: u64,
@@ -203,6 +526,28 @@ pub struct Submission {
     kind: SubmissionKind,
 }
 
+/// Submissions are equal when they have the same `view_id`, even if other
+/// details (like the description) were parsed from different pages.
+impl PartialEq for Submission {
+    fn eq(&self, other: &Self) -> bool {
+        self.view_id == other.view_id
+    }
+}
+
+impl Eq for Submission {}
+
+impl Hash for Submission {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.view_id.hash(state);
+    }
+}
+
+impl Permalink for Submission {
+    fn permalink(&self) -> Url {
+        crate::keys::ViewKey::from(self).into()
+    }
+}
+
 impl From<Submission> for crate::keys::ViewKey {
     fn from(sub: Submission) -> Self {
         Self {
@@ -219,21 +564,22 @@ impl From<&Submission> for crate::keys::ViewKey {
     }
 }
 
+impl From<Submission> for crate::keys::CommentReplyKey {
+    fn from(sub: Submission) -> Self {
+        From::from(&sub)
+    }
+}
+
+impl From<&Submission> for crate::keys::CommentReplyKey {
+    fn from(sub: &Submission) -> Self {
+        Self::view(sub.view_id)
+    }
+}
+
 impl Submission {
     pub fn preview(&self, sz: PreviewSize) -> Url {
-        let pixels = match sz {
-            PreviewSize::Xxxl => 600,
-            PreviewSize::Xxl => 400,
-            PreviewSize::Xl => 300,
-            PreviewSize::L => 250,
-            PreviewSize::M => 200,
-            PreviewSize::S => 150,
-            PreviewSize::Xs => 120,
-            PreviewSize::Xxs => 100,
-            PreviewSize::Xxxs => 50,
-        };
-
-        let path = format!("/{}@{}-{}.jpg", self.view_id, pixels, self.created);
+        let path =
+            format!("/{}@{}-{}.jpg", self.view_id, sz.pixels(), self.created);
         self.cdn.join(&path).unwrap()
     }
 
@@ -249,6 +595,11 @@ impl Submission {
         &self.description
     }
 
+    /// The description as plain text, cut to at most `max_chars` characters.
+    pub fn description_summary(&self, max_chars: usize) -> String {
+        crate::html::summary(&self.description, max_chars)
+    }
+
     pub fn rating(&self) -> Rating {
         self.rating
     }
@@ -258,38 +609,59 @@ impl Submission {
     }
 
     pub fn created(&self) -> NaiveDateTime {
-        NaiveDateTime::from_timestamp_opt(self.created as i64, 0)
-            .unwrap_or_else(|| NaiveDateTime::from_timestamp(0, 0))
+        DateTime::from_timestamp(self.created as i64, 0)
+            .unwrap_or_default()
+            .naive_utc()
     }
 
     pub(crate) fn parse_url(url: &Url) -> Result<(Url, u64), ParseError> {
+        let malformed = |reason| parse_error::MalformedCdnUrl {
+            url: url.clone(),
+            reason,
+        };
+
         let root = url.join("./").unwrap();
         let path = url
             .path_segments()
-            .context(parse_error::IncorrectUrl)?
-            .last()
-            .context(parse_error::IncorrectUrl)?;
-
-        let after_sz = path
-            .splitn(2, '-')
-            .last()
-            .context(parse_error::IncorrectUrl)?;
-        let before_ext = after_sz
-            .splitn(2, '.')
-            .next()
-            .context(parse_error::IncorrectUrl)?;
-
-        Ok((root, before_ext.parse()?))
+            .and_then(|mut s| s.next_back())
+            .filter(|p| !p.is_empty())
+            .with_context(|| malformed("no file name"))?;
+
+        let (_, after_sz) = path
+            .split_once('-')
+            .with_context(|| malformed("no size/timestamp separator"))?;
+        let (before_ext, _) = after_sz
+            .split_once('.')
+            .with_context(|| malformed("no file extension"))?;
+
+        let created = before_ext
+            .parse()
+            .ok()
+            .with_context(|| malformed("timestamp is not a number"))?;
+
+        Ok((root, created))
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MiniUser {
     avatar: Url,
     name: String,
     slug: String,
 }
 
+impl From<&MiniUser> for crate::keys::UserKey {
+    fn from(user: &MiniUser) -> Self {
+        Self::new(user.slug.as_str())
+    }
+}
+
+impl From<MiniUser> for crate::keys::UserKey {
+    fn from(user: MiniUser) -> Self {
+        Self::new(user.slug)
+    }
+}
+
 impl MiniUser {
     pub fn avatar(&self) -> &Url {
         &self.avatar
@@ -303,6 +675,10 @@ impl MiniUser {
         &self.name
     }
 
+    pub fn into_slug(self) -> String {
+        self.slug
+    }
+
     pub(crate) fn without_avatar(name: String, slug: String) -> Self {
         // TODO: Sometimes the domain is a2.facdn.net
         Self {
@@ -313,3 +689,157 @@ impl MiniUser {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_abbreviated() {
+        assert_eq!(number_from_str("42").unwrap(), 42);
+        assert_eq!(number_from_str("1.8k").unwrap(), 1800);
+        assert_eq!(number_from_str("2.3m").unwrap(), 2_300_000);
+        assert_eq!(number_from_str("12K").unwrap(), 12_000);
+        assert_eq!(number_from_str("1.25k").unwrap(), 1250);
+        number_from_str("k").unwrap_err();
+        number_from_str("1.xk").unwrap_err();
+        number_from_str("many").unwrap_err();
+
+        assert_eq!(
+            number_from_str("18446744073709551.615k").unwrap(),
+            u64::MAX
+        );
+        assert!(matches!(
+            number_from_str("18446744073709552k"),
+            Err(ParseError::CountTooLarge { .. })
+        ));
+        number_from_str("18446744073709551.616k").unwrap_err();
+        number_from_str("99999999999999999999m").unwrap_err();
+    }
+
+    #[test]
+    fn user_key_case_insensitive() {
+        use crate::keys::{FavoritesKey, FolderKey, UserKey};
+        use std::collections::HashSet;
+        use std::convert::TryFrom;
+
+        let mini = MiniUser {
+            avatar: Url::parse("https://a.furaffinity.net/tehkey.gif").unwrap(),
+            name: "TehKey".into(),
+            slug: "TehKey".into(),
+        };
+
+        let keys = [
+            UserKey::new("TehKey"),
+            UserKey::try_from("https://www.furaffinity.net/user/TehKey/")
+                .unwrap(),
+            UserKey::from(&mini),
+            UserKey::from(mini.clone()),
+            FolderKey::new("TehKey", 1, "stuff").user_key(),
+            FavoritesKey::from(UserKey {
+                slug: "TehKey".into(),
+            })
+            .user(),
+        ];
+
+        for key in keys.iter() {
+            assert_eq!(key.slug, "tehkey");
+        }
+
+        let unique: HashSet<_> = keys.iter().collect();
+        assert_eq!(unique.len(), 1);
+    }
+
+    #[test]
+    fn user_slug_trailing_slash() {
+        assert_eq!(user_slug("/user/foo/").unwrap(), "foo");
+        assert_eq!(user_slug("/user/foo").unwrap(), "foo");
+        user_slug("/view/1/").unwrap_err();
+    }
+
+    #[test]
+    fn parse_cdn_url() {
+        let url =
+            Url::parse("https://t2.facdn.net/38351732@400-1600894374.jpg")
+                .unwrap();
+        let (root, created) = Submission::parse_url(&url).unwrap();
+
+        assert_eq!(root.as_str(), "https://t2.facdn.net/");
+        assert_eq!(created, 1600894374);
+    }
+
+    #[test]
+    fn parse_cdn_url_errors() {
+        let cases = [
+            ("https://t.facdn.net/", "no file name"),
+            (
+                "https://t.facdn.net/38351732.jpg",
+                "no size/timestamp separator",
+            ),
+            (
+                "https://t.facdn.net/38351732@400-1600894374",
+                "no file extension",
+            ),
+            (
+                "https://t.facdn.net/38351732@400-abc.jpg",
+                "timestamp is not a number",
+            ),
+        ];
+
+        for (txt, expected) in cases.iter() {
+            let url = Url::parse(txt).unwrap();
+            match Submission::parse_url(&url) {
+                Err(ParseError::MalformedCdnUrl { url: u, reason }) => {
+                    assert_eq!(u.as_str(), *txt);
+                    assert_eq!(reason, *expected);
+                }
+                other => panic!("unexpected result for {}: {:?}", txt, other),
+            }
+        }
+    }
+
+    #[test]
+    fn preview_size_from_name() {
+        assert_eq!("xxl".parse::<PreviewSize>().unwrap(), PreviewSize::Xxl);
+        assert_eq!("M".parse::<PreviewSize>().unwrap(), PreviewSize::M);
+    }
+
+    #[test]
+    fn preview_size_from_pixels() {
+        assert_eq!("400".parse::<PreviewSize>().unwrap(), PreviewSize::Xxl);
+        assert_eq!(PreviewSize::from_pixels(50), Some(PreviewSize::Xxxs));
+    }
+
+    #[test]
+    fn preview_size_unsupported() {
+        assert!("401".parse::<PreviewSize>().is_err());
+        assert!("huge".parse::<PreviewSize>().is_err());
+        assert_eq!(PreviewSize::from_pixels(401), None);
+    }
+
+    #[test]
+    fn preview_size_pixels_round_trip() {
+        let sizes = [
+            PreviewSize::Xxxs,
+            PreviewSize::Xxs,
+            PreviewSize::Xs,
+            PreviewSize::S,
+            PreviewSize::M,
+            PreviewSize::L,
+            PreviewSize::Xl,
+            PreviewSize::Xxl,
+            PreviewSize::Xxxl,
+        ];
+
+        for sz in sizes.iter().copied() {
+            assert_eq!(PreviewSize::from_pixels(sz.pixels()), Some(sz));
+        }
+    }
+
+    #[test]
+    fn rating_ord() {
+        assert!(Rating::General < Rating::Mature);
+        assert!(Rating::Mature < Rating::Adult);
+        assert!(Rating::General < Rating::Adult);
+    }
+}
@@ -0,0 +1,35 @@
+use scraper::{Html, Selector};
+
+use super::{descriptions, parse_figure, FromHtml, ParseError, Submission};
+
+use url::Url;
+
+#[derive(Debug)]
+pub struct Browse {
+    items: Vec<Submission>,
+}
+
+impl Browse {
+    pub fn items(&self) -> &[Submission] {
+        self.items.as_slice()
+    }
+
+    pub fn into_items(self) -> Vec<Submission> {
+        self.items
+    }
+}
+
+impl FromHtml for Browse {
+    fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        let mut descriptions = descriptions(doc)?;
+
+        let figure_sel =
+            Selector::parse("section[id^='gallery-'] > figure").unwrap();
+        let items = doc
+            .select(&figure_sel)
+            .map(|f| parse_figure(&url, f, &mut descriptions))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { items })
+    }
+}
@@ -1,27 +1,225 @@
 use chrono::NaiveDateTime;
 
-use crate::keys::CommentReplyKey;
+use crate::keys::{CommentReplyKey, JournalKey, UserKey, ViewKey};
 
-use scraper::ElementRef;
+use scraper::{ElementRef, Html, Selector};
 
-use snafu::ensure;
+use serde::{Deserialize, Serialize};
 
-use super::{parse_error, MiniUser, ParseError};
+use snafu::{ensure, OptionExt};
+
+use std::collections::HashSet;
+
+use super::{parse_error, FromHtml, MiniUser, ParseError};
 
 use url::Url;
 
-#[derive(Debug, Clone, Copy)]
+/// Whether the comment with the given id is on the page with its text.
+pub(crate) fn is_visible(doc: &Html, comment_id: u64) -> bool {
+    let css = format!(
+        ".comment_container a.comment_anchor[id='cid:{}'] ~ * .comment_text",
+        comment_id
+    );
+    let sel = Selector::parse(&css).unwrap();
+    doc.select(&sel).next().is_some()
+}
+
+/// Everyone with a visible comment, in order of their first comment, counting
+/// each slug once.
+pub(crate) fn commenters(comments: &[CommentContainer]) -> Vec<&MiniUser> {
+    let mut seen = HashSet::new();
+    comments
+        .iter()
+        .filter_map(|c| c.comment.as_ref())
+        .map(|c| &c.commenter)
+        .filter(|u| seen.insert(u.slug()))
+        .collect()
+}
+
+/// The comment with `comment_id` and the comments it replies to, outermost
+/// first. Ancestors are found by depth, so hidden comments are included.
+pub(crate) fn thread(
+    comments: &[CommentContainer],
+    comment_id: u64,
+) -> Option<Vec<&CommentContainer>> {
+    let target = comments.iter().position(|c| c.comment_id == comment_id)?;
+
+    let mut depth = comments[target].depth;
+    let mut thread = vec![&comments[target]];
+
+    for comment in comments[..target].iter().rev() {
+        if depth == 0 {
+            break;
+        }
+
+        if comment.depth < depth {
+            depth = comment.depth;
+            thread.push(comment);
+        }
+    }
+
+    thread.reverse();
+    Some(thread)
+}
+
+/// A comment together with the comments it replies to.
+#[derive(Debug, Clone)]
+pub struct CommentContext {
+    pub(crate) thread: Vec<CommentContainer>,
+}
+
+impl CommentContext {
+    /// The comment that was asked for.
+    pub fn target(&self) -> &CommentContainer {
+        self.thread.last().unwrap()
+    }
+
+    /// The comments `target` replies to, outermost first.
+    pub fn ancestors(&self) -> &[CommentContainer] {
+        &self.thread[..self.thread.len() - 1]
+    }
+
+    /// Ancestors followed by the target.
+    pub fn thread(&self) -> &[CommentContainer] {
+        &self.thread
+    }
+}
+
+/// The commenter's slug, taken from the `/user/` link around the avatar.
+/// The avatar's `alt` is only used when there is no link, since it sometimes
+/// holds the display name instead.
+fn commenter_slug(avatar: ElementRef) -> Result<String, ParseError> {
+    let link = avatar
+        .parent()
+        .and_then(ElementRef::wrap)
+        .and_then(|p| p.value().attr("href"));
+
+    match link {
+        Some(href) if href.starts_with("/user/") => {
+            Ok(super::user_slug(href)?.to_string())
+        }
+        _ => Ok(super::attr(avatar, "alt")?.to_string()),
+    }
+}
+
+/// The id of the most recent comment on the page left by `slug`.
+pub(crate) fn newest_by(doc: &Html, slug: &str) -> Result<u64, ParseError> {
+    let sel = Selector::parse(".comment_container").unwrap();
+
+    let mut newest = None;
+    for elem in doc.select(&sel) {
+        let avatar =
+            match super::select_first_elem(elem, "img.comment_useravatar") {
+                Ok(a) => a,
+                Err(ParseError::MissingElement { .. }) => continue,
+                Err(e) => return Err(e),
+            };
+
+        if !commenter_slug(avatar)?.eq_ignore_ascii_case(slug) {
+            continue;
+        }
+
+        let id_elem =
+            super::select_first_elem(elem, "a.comment_anchor[id^='cid:']")?;
+        let comment_id: u64 = super::attr(id_elem, "id")?[4..].parse()?;
+
+        newest = newest.max(Some(comment_id));
+    }
+
+    newest.context(parse_error::MissingElement {
+        selector: ".comment_container",
+    })
+}
+
+/// The form for commenting on a submission or journal, or replying to a
+/// comment. Hidden fields, like any anti-spam token, are copied as-is so they
+/// can be posted back.
+#[derive(Debug, Clone)]
+pub(crate) struct ReplyForm {
+    pub(crate) action: Url,
+    pub(crate) fields: Vec<(String, String)>,
+}
+
+impl ReplyForm {
+    /// The hidden fields of the page's reply form, or none if the page
+    /// doesn't have one (when logged out, or comments are disabled).
+    pub(crate) fn fields_of(
+        url: &Url,
+        doc: &Html,
+    ) -> Result<Vec<(String, String)>, ParseError> {
+        match Self::from_html(url.clone(), doc) {
+            Ok(form) => Ok(form.fields),
+            Err(ParseError::MissingElement { .. }) => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl FromHtml for ReplyForm {
+    fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        let form_sel = Selector::parse("form").unwrap();
+        let form = doc
+            .select(&form_sel)
+            .find(|f| {
+                super::select_first_elem(*f, "textarea[name='reply']").is_ok()
+            })
+            .context(parse_error::MissingElement {
+                selector: "form textarea[name='reply']",
+            })?;
+
+        let action = match form.value().attr("action") {
+            Some(a) => url.join(a)?,
+            None => url,
+        };
+
+        let fields = super::hidden_fields(form)?;
+
+        Ok(Self { action, fields })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub(crate) enum CommentRoot {
     View(u64),
     Journal(u64),
 }
 
-#[derive(Debug, Clone)]
+/// Why a comment's text is or isn't shown, taken from the placeholder FA
+/// renders in place of hidden comments. Placeholders with unrecognized
+/// wording, including comments hidden by the page's owner, are kept as
+/// [`Other`](Self::Other).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum CommentState {
+    Visible,
+    RemovedByAdmin,
+    /// Hidden by the commenter themselves.
+    RemovedByUser,
+    Other(String),
+}
+
+impl CommentState {
+    fn from_placeholder(text: &str) -> Self {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        match text.as_str() {
+            "Comment hidden by its owner" => CommentState::RemovedByUser,
+            "Comment hidden by the Administration" => {
+                CommentState::RemovedByAdmin
+            }
+            _ => CommentState::Other(text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommentContainer {
     pub(crate) root: CommentRoot,
     pub(crate) comment_id: u64,
 
+    pub(crate) index: usize,
     pub(crate) depth: u8,
+    pub(crate) state: CommentState,
     pub(crate) comment: Option<Comment>,
 }
 
@@ -41,6 +239,30 @@ impl From<&CommentContainer> for CommentReplyKey {
 }
 
 impl CommentContainer {
+    pub fn comment_id(&self) -> u64 {
+        self.comment_id
+    }
+
+    /// Position of this comment in the page, counting from zero in the order
+    /// FurAffinity displays the thread.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn root_view_key(&self) -> Option<ViewKey> {
+        match self.root {
+            CommentRoot::View(id) => Some(ViewKey { view_id: id }),
+            _ => None,
+        }
+    }
+
+    pub fn root_journal_key(&self) -> Option<JournalKey> {
+        match self.root {
+            CommentRoot::Journal(id) => Some(JournalKey { journal_id: id }),
+            _ => None,
+        }
+    }
+
     pub fn depth(&self) -> u8 {
         self.depth
     }
@@ -49,6 +271,25 @@ impl CommentContainer {
         self.comment.as_ref()
     }
 
+    /// A key for the comment this one replies to, if it is a reply and its
+    /// text is visible.
+    pub fn parent_key(&self) -> Option<CommentReplyKey> {
+        let parent_id = self.comment.as_ref()?.parent_id?;
+
+        let key = match self.root {
+            CommentRoot::View(_) => CommentReplyKey::view_comment(parent_id),
+            CommentRoot::Journal(_) => {
+                CommentReplyKey::journal_comment(parent_id)
+            }
+        };
+
+        Some(key)
+    }
+
+    pub fn state(&self) -> &CommentState {
+        &self.state
+    }
+
     fn extract_width(elem: ElementRef) -> Result<u8, ParseError> {
         let style = super::attr(elem, "style")?;
         ensure!(
@@ -66,6 +307,7 @@ impl CommentContainer {
     pub(crate) fn extract(
         url: &Url,
         root: CommentRoot,
+        index: usize,
         elem: ElementRef,
     ) -> Result<Self, ParseError> {
         let width = Self::extract_width(elem)?;
@@ -80,10 +322,18 @@ impl CommentContainer {
         let text = match text_res {
             Ok(t) => crate::html::simplify(url, t),
             Err(ParseError::MissingElement { .. }) => {
+                let placeholder =
+                    super::select_first_elem(elem, ".body > strong")
+                        .or_else(|_| super::select_first_elem(elem, ".body"))
+                        .map(super::text)
+                        .unwrap_or_default();
+
                 return Ok(CommentContainer {
+                    state: CommentState::from_placeholder(&placeholder),
                     comment: None,
                     comment_id,
                     root,
+                    index,
                     depth,
                 });
             }
@@ -108,11 +358,19 @@ impl CommentContainer {
             super::select_first_elem(elem, ".comment-date .popup_date")?;
         let posted = super::datetime(posted_elem)?;
 
+        let edited_res =
+            super::select_first_elem(elem, ".comment-edited .popup_date");
+        let edited = match edited_res {
+            Ok(e) => Some(super::datetime(e)?),
+            Err(ParseError::MissingElement { .. }) => None,
+            Err(e) => return Err(e),
+        };
+
         let avatar_elem =
             super::select_first_elem(elem, "img.comment_useravatar")?;
         let avatar = url.join(super::attr(avatar_elem, "src")?)?;
 
-        let slug = super::attr(avatar_elem, "alt")?.to_string();
+        let slug = commenter_slug(avatar_elem)?;
 
         let name_elem = super::select_first_elem(elem, ".comment_username h3")?;
         let name = super::text(name_elem);
@@ -121,21 +379,25 @@ impl CommentContainer {
             depth,
             root,
             comment_id,
+            index,
+            state: CommentState::Visible,
             comment: Some(Comment {
                 parent_id,
                 text,
                 posted,
+                edited,
                 commenter: MiniUser { avatar, slug, name },
             }),
         })
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comment {
     pub(crate) parent_id: Option<u64>,
     pub(crate) commenter: MiniUser,
     pub(crate) posted: NaiveDateTime,
+    pub(crate) edited: Option<NaiveDateTime>,
     pub(crate) text: String,
 }
 
@@ -148,11 +410,104 @@ impl Comment {
         &self.commenter
     }
 
+    pub fn commenter_key(&self) -> UserKey {
+        UserKey::from(&self.commenter)
+    }
+
     pub fn posted(&self) -> NaiveDateTime {
         self.posted
     }
 
+    pub fn edited(&self) -> Option<NaiveDateTime> {
+        self.edited
+    }
+
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// The comment as plain text, cut to at most `max_chars` characters.
+    pub fn summary(&self, max_chars: usize) -> String {
+        crate::html::summary(&self.text, max_chars)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_state_from_placeholder() {
+        assert_eq!(
+            CommentState::from_placeholder("Comment hidden by its owner"),
+            CommentState::RemovedByUser
+        );
+        assert_eq!(
+            CommentState::from_placeholder(
+                "Comment hidden by  the Administration"
+            ),
+            CommentState::RemovedByAdmin
+        );
+        assert_eq!(
+            CommentState::from_placeholder("Reported to the page owner"),
+            CommentState::Other("Reported to the page owner".into())
+        );
+    }
+
+    #[test]
+    fn newest_comment_by_user() {
+        let text = include_str!("../../tests/resources/view/image.html");
+        let doc = Html::parse_document(text);
+
+        assert_eq!(newest_by(&doc, "Luminaria").unwrap(), 150154279);
+        assert!(newest_by(&doc, "nobody-at-all").is_err());
+    }
+
+    #[test]
+    fn reply_form_fields() {
+        let text = include_str!("../../tests/resources/view/image.html")
+            .replace(
+                r#"<input type="hidden" name="f" value="0"/>"#,
+                r#"<input type="hidden" name="f" value="0"/>
+                   <input type="hidden" name="key" value="abc123"/>"#,
+            );
+        let doc = Html::parse_document(&text);
+        let url =
+            Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+        let form = ReplyForm::from_html(url.clone(), &doc).unwrap();
+        assert_eq!(form.action, url);
+
+        let fields: Vec<_> = form
+            .fields
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("f", "0"),
+                ("key", "abc123"),
+                ("action", "reply"),
+                ("replyto", "")
+            ]
+        );
+    }
+
+    #[test]
+    fn reply_form_missing() {
+        let doc = Html::parse_document("<form><textarea name='x'></form>");
+        let url = Url::parse("https://www.furaffinity.net/view/1/").unwrap();
+
+        assert!(ReplyForm::fields_of(&url, &doc).unwrap().is_empty());
+    }
+
+    #[test]
+    fn comment_is_visible() {
+        let text = include_str!("../../tests/resources/view/image.html");
+        let doc = Html::parse_document(text);
+
+        assert!(is_visible(&doc, 150154279));
+        assert!(!is_visible(&doc, 1));
+    }
 }
@@ -0,0 +1,70 @@
+use crate::keys::FavoritesKey;
+
+use scraper::{Html, Selector};
+
+use super::{
+    attr, descriptions, parse_figure, FromHtml, ParseError, Submission,
+};
+
+use std::convert::TryFrom;
+
+use url::Url;
+
+#[derive(Debug)]
+pub struct Favorites {
+    items: Vec<Submission>,
+    next: Option<FavoritesKey>,
+    prev: Option<FavoritesKey>,
+}
+
+impl Favorites {
+    pub fn next(&self) -> Option<&FavoritesKey> {
+        self.next.as_ref()
+    }
+
+    pub fn prev(&self) -> Option<&FavoritesKey> {
+        self.prev.as_ref()
+    }
+
+    pub fn items(&self) -> &[Submission] {
+        self.items.as_slice()
+    }
+
+    pub fn into_items(self) -> Vec<Submission> {
+        self.items
+    }
+}
+
+impl FromHtml for Favorites {
+    fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        let mut next = None;
+        let mut prev = None;
+
+        let nav_sel = Selector::parse("a.button[href^='/favorites/']").unwrap();
+        for nav_elem in doc.select(&nav_sel) {
+            let href = attr(nav_elem, "href")?;
+            let slot = if href.ends_with("/next") {
+                &mut next
+            } else if href.ends_with("/prev") {
+                &mut prev
+            } else {
+                continue;
+            };
+
+            let key = FavoritesKey::try_from(url.join(href)?)
+                .map_err(|_| ParseError::IncorrectUrl)?;
+            *slot = Some(key);
+        }
+
+        let mut descriptions = descriptions(doc)?;
+
+        let figure_sel =
+            Selector::parse("section[id^='gallery-'] > figure").unwrap();
+        let items = doc
+            .select(&figure_sel)
+            .map(|f| parse_figure(&url, f, &mut descriptions))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { items, next, prev })
+    }
+}
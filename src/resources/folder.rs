@@ -0,0 +1,76 @@
+use crate::keys::FolderKey;
+
+use scraper::{Html, Selector};
+
+use super::{
+    attr, descriptions, parse_figure, text, FromHtml, ParseError, Submission,
+};
+
+use std::convert::TryFrom;
+
+use url::Url;
+
+/// One page of submissions from a gallery folder.
+#[derive(Debug)]
+pub struct Folder {
+    items: Vec<Submission>,
+    next: Option<FolderKey>,
+    prev: Option<FolderKey>,
+}
+
+impl Folder {
+    pub fn next(&self) -> Option<&FolderKey> {
+        self.next.as_ref()
+    }
+
+    pub fn prev(&self) -> Option<&FolderKey> {
+        self.prev.as_ref()
+    }
+
+    pub fn items(&self) -> &[Submission] {
+        self.items.as_slice()
+    }
+
+    pub fn into_items(self) -> Vec<Submission> {
+        self.items
+    }
+}
+
+impl FromHtml for Folder {
+    fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        let mut next = None;
+        let mut prev = None;
+
+        // Gallery pages navigate with forms, each holding a single button.
+        let nav_sel =
+            Selector::parse("form[action^='/gallery/'][action*='/folder/']")
+                .unwrap();
+        for nav_elem in doc.select(&nav_sel) {
+            let label = text(nav_elem);
+            let label = label.trim();
+            let slot = if label.starts_with("Next") {
+                &mut next
+            } else if label.starts_with("Prev") {
+                &mut prev
+            } else {
+                continue;
+            };
+
+            let action = attr(nav_elem, "action")?;
+            let key = FolderKey::try_from(url.join(action)?)
+                .map_err(|_| ParseError::IncorrectUrl)?;
+            *slot = Some(key);
+        }
+
+        let mut descriptions = descriptions(doc)?;
+
+        let figure_sel =
+            Selector::parse("section[id^='gallery-'] > figure").unwrap();
+        let items = doc
+            .select(&figure_sel)
+            .map(|f| parse_figure(&url, f, &mut descriptions))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { items, next, prev })
+    }
+}
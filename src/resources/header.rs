@@ -1,15 +1,23 @@
 use scraper::{ElementRef, Html, Selector};
 
-use snafu::{ensure, OptionExt};
+use snafu::OptionExt;
 
-use super::{parse_error, FromHtml, MiniUser, ParseError};
+use super::{parse_error, FromHtml, MiniUser, ParseError, Rating};
 
 use url::Url;
 
+/// Whether the page was rendered for a logged-in session, regardless of
+/// whether the rest of the header could be parsed.
+pub(crate) fn is_logged_in(html: &Html) -> bool {
+    let sel = Selector::parse("img.loggedin_user_avatar").unwrap();
+    html.select(&sel).next().is_some()
+}
+
 #[derive(Debug, Clone)]
 pub struct Header {
     me: MiniUser,
     notifications: Notifications,
+    content_filter: Rating,
 }
 
 impl Header {
@@ -20,6 +28,14 @@ impl Header {
     pub fn notifications(&self) -> &Notifications {
         &self.notifications
     }
+
+    /// The highest rating this account will currently display.
+    ///
+    /// The SFW toggle only distinguishes SFW from NSFW, so with it off this
+    /// is `Adult` even if the account settings only allow Mature.
+    pub fn content_filter(&self) -> Rating {
+        self.content_filter
+    }
 }
 
 impl FromHtml for Header {
@@ -36,14 +52,22 @@ impl FromHtml for Header {
             })?;
         let slug_elem = ElementRef::wrap(slug_node).unwrap();
         let slug_txt = super::attr(slug_elem, "href")?;
-        ensure!(slug_txt.starts_with("/user/"), parse_error::IncorrectUrl);
-        ensure!(slug_txt.ends_with('/'), parse_error::IncorrectUrl);
-        let slug = slug_txt[6..slug_txt.len() - 1].to_string();
+        let slug = super::user_slug(slug_txt)?.to_string();
 
         let notifications = Notifications::from_html(url, html)?;
 
+        // Without a toggle, assume only general content is shown.
+        let content_filter =
+            match super::select_first(html, "input#sfw-toggle-mobile") {
+                Ok(e) if e.value().attr("checked").is_some() => Rating::General,
+                Ok(_) => Rating::Adult,
+                Err(ParseError::MissingElement { .. }) => Rating::General,
+                Err(e) => return Err(e),
+            };
+
         Ok(Self {
             notifications,
+            content_filter,
             me: MiniUser { avatar, name, slug },
         })
     }
@@ -62,12 +86,27 @@ pub struct Notifications {
 }
 
 impl Notifications {
+    pub fn total(&self) -> u64 {
+        self.submissions
+            + self.journals
+            + self.watches
+            + self.comments
+            + self.favorites
+            + self.trouble_tickets
+            + self.notes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
     fn suffix(suffix: &str, text: &str) -> Option<u64> {
         if !text.ends_with(suffix) {
             return None;
         }
 
-        let num_text = text[..text.len() - suffix.len()].trim();
+        let num_text =
+            text[..text.len() - suffix.len()].trim().replace(',', "");
 
         num_text.parse().ok()
     }
@@ -92,6 +131,7 @@ impl FromHtml for Notifications {
         for elem in bar.select(&selector) {
             let text = super::text(elem);
 
+            // `TT` has to be checked before the single letter suffixes.
             if let Some(tt) = Self::suffix("TT", &text) {
                 n.trouble_tickets += tt;
             } else if let Some(s) = Self::suffix("S", &text) {
@@ -112,3 +152,41 @@ impl FromHtml for Notifications {
         Ok(n)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notifications(bar: &str) -> Notifications {
+        let txt = format!(
+            r#"<div id="ddmenu"><div class="message-bar-desktop">{}</div></div>"#,
+            bar
+        );
+        let html = Html::parse_document(&txt);
+        let url = Url::parse("https://www.furaffinity.net/").unwrap();
+        Notifications::from_html(url, &html).unwrap()
+    }
+
+    #[test]
+    fn notifications_thousands_separator() {
+        let n = notifications(
+            r#"<a class="notification-container">1,024 C</a>
+               <a class="notification-container">12,345 S</a>"#,
+        );
+
+        assert_eq!(n.comments, 1024);
+        assert_eq!(n.submissions, 12345);
+    }
+
+    #[test]
+    fn notifications_trouble_tickets() {
+        let n = notifications(
+            r#"<a class="notification-container">3 TT</a>
+               <a class="notification-container">2 N</a>"#,
+        );
+
+        assert_eq!(n.trouble_tickets, 3);
+        assert_eq!(n.notes, 2);
+        assert_eq!(n.total(), 5);
+    }
+}
@@ -1,18 +1,20 @@
 use chrono::NaiveDateTime;
 
-use crate::html::simplify;
+use crate::html::{quotes, simplify, Quote};
 use crate::keys::{CommentReplyKey, JournalKey};
 
 use scraper::{Html, Selector};
 
 use snafu::{ensure, OptionExt};
 
-use super::comment::{CommentContainer, CommentRoot};
+use super::comment::{CommentContainer, CommentRoot, ReplyForm};
 use super::{
     parse_error, select_first, select_first_elem, FromHtml, MiniUser,
-    ParseError,
+    ParseError, Permalink,
 };
 
+use std::collections::HashMap;
+
 use url::Url;
 
 #[derive(Debug, Clone)]
@@ -24,12 +26,15 @@ pub struct Journal {
     header: Option<String>,
     footer: Option<String>,
     content: String,
+    content_quotes: Vec<Quote>,
 
     posted: NaiveDateTime,
+    edited: Option<NaiveDateTime>,
 
     n_comments: u64,
 
     comments: Vec<CommentContainer>,
+    reply_fields: Vec<(String, String)>,
 }
 
 impl Journal {
@@ -50,28 +55,88 @@ impl Journal {
     }
 
     pub fn footer(&self) -> Option<&str> {
-        self.header.as_deref()
+        self.footer.as_deref()
     }
 
     pub fn content(&self) -> &str {
         &self.content
     }
 
+    /// Quotes in the journal body, with their attribution kept separate
+    /// instead of flattened into the simplified HTML.
+    pub fn content_quotes(&self) -> &[Quote] {
+        &self.content_quotes
+    }
+
     pub fn posted(&self) -> NaiveDateTime {
         self.posted
     }
 
+    pub fn edited(&self) -> Option<NaiveDateTime> {
+        self.edited
+    }
+
+    /// Comment count as reported by FurAffinity. Hidden or removed comments
+    /// are still counted here, so this only equals `comments().len()` when
+    /// every comment in the thread is visible.
+    pub fn n_comments_reported(&self) -> u64 {
+        self.n_comments
+    }
+
+    #[deprecated(since = "0.1.1", note = "use `n_comments_reported`")]
     pub fn n_comments(&self) -> u64 {
         self.n_comments
     }
 
+    /// Number of comment containers actually present on the page.
+    pub fn n_comments_displayed(&self) -> usize {
+        self.comments.len()
+    }
+
+    /// Hidden fields of the comment form, including any token FurAffinity
+    /// expects back with a reply. Empty when there is no form.
+    pub fn reply_fields(&self) -> &[(String, String)] {
+        &self.reply_fields
+    }
+
+    /// Comments in the order they appear on the page, so that
+    /// `comments()[i].index() == i`.
     pub fn comments(&self) -> &[CommentContainer] {
         &self.comments
     }
+
+    pub fn comments_by_id(&self) -> HashMap<u64, &CommentContainer> {
+        self.comments.iter().map(|c| (c.comment_id, c)).collect()
+    }
+
+    /// The comment with `comment_id` and the comments it replies to,
+    /// outermost first.
+    pub fn comment_thread(
+        &self,
+        comment_id: u64,
+    ) -> Option<Vec<&CommentContainer>> {
+        super::comment::thread(&self.comments, comment_id)
+    }
+
+    /// The users who left visible comments, each listed once in order of
+    /// their first comment.
+    pub fn commenters(&self) -> Vec<&MiniUser> {
+        super::comment::commenters(&self.comments)
+    }
 }
 
 impl FromHtml for Journal {
     fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        ensure!(!super::is_not_found(doc), parse_error::NotFound);
+        ensure!(
+            !super::is_account_disabled(doc),
+            parse_error::AccountDisabled
+        );
+        ensure!(
+            !super::is_registration_required(&url, doc),
+            parse_error::RegistrationRequired
+        );
+
         let mut segments =
             url.path_segments().context(parse_error::IncorrectUrl)?;
         ensure!(
@@ -98,14 +163,27 @@ impl FromHtml for Journal {
 
         let content_elem = select_first_elem(j, ".journal-content")?;
         let content = simplify(&url, content_elem);
+        let content_quotes = quotes(&url, content_elem);
 
         let title_elem = select_first(doc, "h2.journal-title")?;
         let title = super::text(title_elem);
 
+        let date_sel =
+            Selector::parse("h2.journal-title + div .popup_date").unwrap();
+        let mut dates = doc.select(&date_sel);
+
         let posted_elem =
-            select_first(doc, "h2.journal-title + div .popup_date")?;
+            dates.next().context(parse_error::MissingElement {
+                selector: "h2.journal-title + div .popup_date",
+            })?;
         let posted = super::datetime(posted_elem)?;
 
+        // An edited journal has a second date following the original one.
+        let edited = match dates.next() {
+            Some(e) => Some(super::datetime(e)?),
+            None => None,
+        };
+
         let username_sel = "#user-profile .username h2";
         let username_elem = select_first(doc, username_sel)?;
         let username_txt = super::text(username_elem);
@@ -120,21 +198,21 @@ impl FromHtml for Journal {
 
         let slug_elem =
             select_first(doc, "#user-profile .user-nav a[href^='/user/']")?;
-        let slug_attr = &super::attr(slug_elem, "href")?[6..];
-        let slug = if let Some(stripped) = slug_attr.strip_suffix('/') {
-            stripped
-        } else {
-            slug_attr
-        };
+        let slug = super::user_slug(super::attr(slug_elem, "href")?)?;
 
         let avatar_elem =
             select_first(doc, "#user-profile img.user-nav-avatar")?;
         let avatar_txt = super::attr(avatar_elem, "src")?;
         let avatar = url.join(avatar_txt)?;
 
-        let n_comments_elem =
-            select_first(doc, ".journal-body-theme + div.section-footer span")?;
-        let n_comments = super::number(n_comments_elem)?;
+        // The comment count is missing when comments are disabled.
+        let n_comments_res =
+            select_first(doc, ".journal-body-theme + div.section-footer span");
+        let n_comments = match n_comments_res {
+            Ok(e) => super::number(e)?,
+            Err(ParseError::MissingElement { .. }) => 0,
+            Err(e) => return Err(e),
+        };
 
         let comment_root = CommentRoot::Journal(journal_id);
 
@@ -142,9 +220,12 @@ impl FromHtml for Journal {
             Selector::parse("#comments-journal .comment_container").unwrap();
         let comments = doc
             .select(&comment_sel)
-            .map(|c| CommentContainer::extract(&url, comment_root, c))
+            .enumerate()
+            .map(|(i, c)| CommentContainer::extract(&url, comment_root, i, c))
             .collect::<Result<Vec<_>, _>>()?;
 
+        let reply_fields = ReplyForm::fields_of(&url, doc)?;
+
         Ok(Self {
             author: MiniUser {
                 name: username.to_string(),
@@ -153,13 +234,16 @@ impl FromHtml for Journal {
             },
             journal_id,
             content,
+            content_quotes,
             title,
             posted,
+            edited,
             header,
             footer,
 
             n_comments,
             comments,
+            reply_fields,
         })
     }
 }
@@ -176,6 +260,12 @@ impl From<Journal> for CommentReplyKey {
     }
 }
 
+impl Permalink for Journal {
+    fn permalink(&self) -> Url {
+        JournalKey::from(self).into()
+    }
+}
+
 impl From<&Journal> for JournalKey {
     fn from(v: &Journal) -> Self {
         Self {
@@ -4,16 +4,18 @@ use crate::keys::{CommentReplyKey, JournalKey, ViewKey};
 use crate::resources::comment::CommentRoot;
 use crate::resources::{
     attr, datetime, parse_error, select_first_elem, text, FromHtml, MiniUser,
-    ParseError,
+    ParseError, Permalink,
 };
 
 use scraper::{ElementRef, Html, Selector};
 
+use serde::{Deserialize, Serialize};
+
 use snafu::{ensure, OptionExt};
 
 use url::Url;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MiniComment {
     root: CommentRoot,
     title: String,
@@ -63,12 +65,30 @@ impl MiniComment {
     pub fn author(&self) -> &MiniUser {
         &self.author
     }
+
+    pub fn posted(&self) -> NaiveDateTime {
+        self.posted
+    }
+}
+
+/// Links to the comment's anchor on the submission or journal page.
+impl Permalink for MiniComment {
+    fn permalink(&self) -> Url {
+        let mut url = match self.root {
+            CommentRoot::View(view_id) => Url::from(ViewKey { view_id }),
+            CommentRoot::Journal(journal_id) => {
+                Url::from(JournalKey { journal_id })
+            }
+        };
+        url.set_fragment(Some(&format!("cid:{}", self.comment_id)));
+        url
+    }
 }
 
 // TODO: impl From<MiniComment> for Option<ViewKey> ??
 // TODO: impl From<MiniComment> for Option<JournalKey> ??
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommentMsg {
     comment_id: u64,
     is_journal: bool,
@@ -144,12 +164,20 @@ impl CommentMsg {
         })
     }
 
+    pub fn comment_id(&self) -> u64 {
+        self.comment_id
+    }
+
+    pub fn is_journal(&self) -> bool {
+        self.is_journal
+    }
+
     pub fn comment(&self) -> Option<&MiniComment> {
         self.comment.as_ref()
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MiniJournal {
     author: MiniUser,
     posted: NaiveDateTime,
@@ -220,7 +248,7 @@ impl From<MiniJournal> for JournalKey {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MiniShout {
     author: MiniUser,
     posted: NaiveDateTime,
@@ -236,13 +264,17 @@ impl MiniShout {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShoutMsg {
     shout_id: u64,
     shout: Option<MiniShout>,
 }
 
 impl ShoutMsg {
+    pub fn shout_id(&self) -> u64 {
+        self.shout_id
+    }
+
     pub fn shout(&self) -> Option<&MiniShout> {
         self.shout.as_ref()
     }
@@ -291,10 +323,10 @@ impl ShoutMsg {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Watch {
     user: MiniUser,
-    when: NaiveDateTime,
+    when: Option<NaiveDateTime>,
 }
 
 impl Watch {
@@ -302,12 +334,12 @@ impl Watch {
         &self.user
     }
 
-    pub fn when(&self) -> NaiveDateTime {
+    pub fn when(&self) -> Option<NaiveDateTime> {
         self.when
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WatchMsg {
     watch_id: u64,
     watch: Option<Watch>,
@@ -347,8 +379,11 @@ impl WatchMsg {
         let avatar_src = attr(avatar_img, "src")?;
         let avatar = url.join(avatar_src)?;
 
-        let when_elem = select_first_elem(elem, ".info .popup_date")?;
-        let when = datetime(when_elem)?;
+        let when = match select_first_elem(elem, ".info .popup_date") {
+            Ok(e) => Some(datetime(e)?),
+            Err(ParseError::MissingElement { .. }) => None,
+            Err(e) => return Err(e),
+        };
 
         let name_elem = select_first_elem(elem, ".info span:first-child")?;
         let name = text(name_elem);
@@ -362,12 +397,16 @@ impl WatchMsg {
         })
     }
 
+    pub fn watch_id(&self) -> u64 {
+        self.watch_id
+    }
+
     pub fn watch(&self) -> Option<&Watch> {
         self.watch.as_ref()
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Favorite {
     favorite_id: u64,
     user: MiniUser,
@@ -377,6 +416,10 @@ pub struct Favorite {
 }
 
 impl Favorite {
+    pub fn favorite_id(&self) -> u64 {
+        self.favorite_id
+    }
+
     pub fn user(&self) -> &MiniUser {
         &self.user
     }
@@ -444,16 +487,49 @@ impl Favorite {
     }
 }
 
-#[derive(Debug)]
+impl From<&Favorite> for ViewKey {
+    fn from(f: &Favorite) -> ViewKey {
+        Self { view_id: f.view_id }
+    }
+}
+
+impl From<Favorite> for ViewKey {
+    fn from(f: Favorite) -> ViewKey {
+        Self::from(&f)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Others {
     journals: Vec<MiniJournal>,
     watches: Vec<WatchMsg>,
     comments: Vec<CommentMsg>,
     favorites: Vec<Favorite>,
     shouts: Vec<ShoutMsg>,
+
+    // Only meaningful right after parsing, and `ParseError` can't be
+    // serialized anyway.
+    #[serde(skip)]
+    errors: Vec<ParseError>,
 }
 
 impl Others {
+    /// Like `from_html`, but fails on the first notification that cannot be
+    /// parsed instead of collecting it into `errors`.
+    pub fn from_html_strict(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        let mut page = Self::from_html(url, doc)?;
+        if page.errors.is_empty() {
+            Ok(page)
+        } else {
+            Err(page.errors.remove(0))
+        }
+    }
+
+    /// Errors from individual notifications that were skipped while parsing.
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
     pub fn watches(&self) -> &[WatchMsg] {
         &self.watches
     }
@@ -475,13 +551,27 @@ impl Others {
     }
 }
 
+fn collect<T>(
+    items: &mut Vec<T>,
+    errors: &mut Vec<ParseError>,
+    result: Result<T, ParseError>,
+) {
+    match result {
+        Ok(item) => items.push(item),
+        Err(e) => errors.push(e),
+    }
+}
+
 impl FromHtml for Others {
     fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        let mut errors = Vec::new();
+
         let mut watches = Vec::new();
         let watches_sel =
             Selector::parse("#messages-watches .message-stream > li").unwrap();
         for watch_elem in doc.select(&watches_sel) {
-            watches.push(WatchMsg::extract(&url, watch_elem)?);
+            let watch = WatchMsg::extract(&url, watch_elem);
+            collect(&mut watches, &mut errors, watch);
         }
 
         let mut comments = Vec::new();
@@ -491,29 +581,33 @@ impl FromHtml for Others {
         )
         .unwrap();
         for comment_elem in doc.select(&comments_sel) {
-            comments.push(CommentMsg::extract(&url, comment_elem)?);
+            let comment = CommentMsg::extract(&url, comment_elem);
+            collect(&mut comments, &mut errors, comment);
         }
 
         let mut shouts = Vec::new();
         let shouts_sel =
             Selector::parse("#messages-shouts .message-stream > li").unwrap();
         for shout_elem in doc.select(&shouts_sel) {
-            shouts.push(ShoutMsg::extract(&url, shout_elem)?);
+            let shout = ShoutMsg::extract(&url, shout_elem);
+            collect(&mut shouts, &mut errors, shout);
         }
 
         let mut journals = Vec::new();
         let journals_sel =
             Selector::parse("#messages-journals .message-stream > li").unwrap();
         for journal_elem in doc.select(&journals_sel) {
-            journals.push(MiniJournal::extract(&url, journal_elem)?);
+            let journal = MiniJournal::extract(&url, journal_elem);
+            collect(&mut journals, &mut errors, journal);
         }
 
         let mut favorites = Vec::new();
         let favs_sel =
             Selector::parse("#messages-favorites .message-stream > li")
                 .unwrap();
-        for journal_elem in doc.select(&favs_sel) {
-            favorites.push(Favorite::extract(&url, journal_elem)?);
+        for fav_elem in doc.select(&favs_sel) {
+            let favorite = Favorite::extract(&url, fav_elem);
+            collect(&mut favorites, &mut errors, favorite);
         }
 
         Ok(Self {
@@ -522,6 +616,7 @@ impl FromHtml for Others {
             shouts,
             journals,
             favorites,
+            errors,
         })
     }
 }
@@ -2,13 +2,11 @@ use crate::keys::SubmissionsKey;
 
 use scraper::{Html, Selector};
 
-use serde::Deserialize;
-
-use snafu::{ensure, OptionExt};
+use serde::{Deserialize, Serialize};
 
 use super::super::{
-    attr, parse_error, select_first, select_first_elem, text, FromHtml,
-    MiniUser, ParseError, Rating, Submission, SubmissionKind,
+    attr, descriptions, parse_figure, select_first, FromHtml, ParseError,
+    Rating, Submission,
 };
 
 use std::collections::HashMap;
@@ -16,18 +14,13 @@ use std::convert::TryFrom;
 
 use url::Url;
 
-#[derive(Debug, Deserialize)]
-struct SubInfo {
-    title: String,
-    description: String,
-    username: String,
-    lower: String,
-    avatar_mtime: String,
-}
-
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// Defaults to newest first.
+#[derive(
+    Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
 pub enum Order {
     Ascending,
+    #[default]
     Descending,
 }
 
@@ -40,7 +33,7 @@ impl Order {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Submissions {
     items: Vec<Submission>,
     next: Option<SubmissionsKey>,
@@ -64,6 +57,28 @@ impl Submissions {
         self.items
     }
 
+    /// How many submissions on this page have each rating.
+    pub fn ratings_summary(&self) -> HashMap<Rating, usize> {
+        let mut summary = HashMap::new();
+        for item in &self.items {
+            *summary.entry(item.rating()).or_insert(0) += 1;
+        }
+        summary
+    }
+
+    /// Submissions on this page grouped by the artist's slug, keeping page
+    /// order within each group.
+    pub fn by_artist(&self) -> HashMap<String, Vec<&Submission>> {
+        let mut groups: HashMap<String, Vec<&Submission>> = HashMap::new();
+        for item in &self.items {
+            groups
+                .entry(item.artist().slug().to_owned())
+                .or_default()
+                .push(item);
+        }
+        groups
+    }
+
     fn extract_nav(
         url: &Url,
         doc: &Html,
@@ -105,20 +120,8 @@ impl FromHtml for Submissions {
             Err(e) => return Err(e),
         };
 
-        let script_sel = Selector::parse("script").unwrap();
-        let script_txt = doc
-            .select(&script_sel)
-            .map(text)
-            .find(|x| x.contains("var descriptions ="))
-            .context(parse_error::MissingElement { selector: "script" })?;
-
-        let descriptions_txt = script_txt
-            .split(";\n")
-            .next()
-            .context(parse_error::MissingElement { selector: "script" })?
-            .trim();
-
-        if descriptions_txt.starts_with("var descriptions = []") {
+        let mut descriptions = descriptions(doc)?;
+        if descriptions.is_empty() {
             return Ok(Self {
                 next: None,
                 prev: None,
@@ -126,95 +129,12 @@ impl FromHtml for Submissions {
             });
         }
 
-        ensure!(
-            descriptions_txt.starts_with("var descriptions = {"),
-            parse_error::MissingElement { selector: "script" }
-        );
-        ensure!(
-            descriptions_txt.ends_with('}'),
-            parse_error::MissingElement { selector: "script" }
-        );
-        let descriptions_txt = &descriptions_txt[19..];
-
-        let descriptions_str: HashMap<&str, SubInfo> =
-            serde_json::from_str(descriptions_txt)?;
-
-        let mut descriptions: HashMap<u64, SubInfo> =
-            HashMap::with_capacity(descriptions_str.len());
-
-        for (sid_txt, sub_info) in descriptions_str.into_iter() {
-            let sid = sid_txt.parse()?;
-            descriptions.insert(sid, sub_info);
-        }
-
-        let mut items = vec![];
-
         let figure_sel =
             Selector::parse("section[id^='gallery-'] > figure").unwrap();
-        for figure_elem in doc.select(&figure_sel) {
-            let class = attr(figure_elem, "class")?;
-            let rating = if class.contains("r-adult") {
-                Rating::Adult
-            } else if class.contains("r-mature") {
-                Rating::Mature
-            } else if class.contains("r-general") {
-                Rating::General
-            } else {
-                return Err(ParseError::MissingAttribute {
-                    attribute: "class",
-                });
-            };
-            let kind = if class.contains("t-image") {
-                SubmissionKind::Image
-            } else if class.contains("t-flash") {
-                SubmissionKind::Flash
-            } else if class.contains("t-audio") {
-                SubmissionKind::Audio
-            } else if class.contains("t-text") {
-                SubmissionKind::Text
-            } else {
-                return Err(ParseError::MissingAttribute {
-                    attribute: "class",
-                });
-            };
-
-            let id_attr = attr(figure_elem, "id")?;
-            ensure!(
-                id_attr.starts_with("sid-"),
-                parse_error::MissingAttribute { attribute: "id" }
-            );
-            let view_id = id_attr[4..].parse()?;
-
-            let preview_elem = select_first_elem(figure_elem, "img")?;
-            let preview_attr = attr(preview_elem, "src")?;
-            let preview = url.join(preview_attr)?;
-            let (cdn, created) = Submission::parse_url(&preview)?;
-
-            let sub_info = descriptions.remove(&view_id).unwrap();
-
-            // TODO: sometimes it's a2.facdn.net instead.
-            let avatar = url
-                .join(&format!(
-                    "//a.facdn.net/{}/{}.gif",
-                    sub_info.avatar_mtime, sub_info.lower
-                ))
-                .unwrap();
-
-            items.push(Submission {
-                view_id,
-                rating,
-                cdn,
-                created,
-                kind,
-                title: sub_info.title,
-                description: sub_info.description,
-                artist: MiniUser {
-                    name: sub_info.username,
-                    slug: sub_info.lower,
-                    avatar,
-                },
-            });
-        }
+        let items = doc
+            .select(&figure_sel)
+            .map(|f| parse_figure(&url, f, &mut descriptions))
+            .collect::<Result<_, _>>()?;
 
         Ok(Self { items, next, prev })
     }
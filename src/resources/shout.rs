@@ -0,0 +1,91 @@
+use scraper::{Html, Selector};
+
+use snafu::ensure;
+
+use super::{parse_error, FromHtml, ParseError};
+
+use url::Url;
+
+/// The form for leaving a shout on a user's profile. Hidden fields, like
+/// the form key, are copied as-is so they can be posted back.
+#[derive(Debug, Clone)]
+pub(crate) struct ShoutForm {
+    pub(crate) action: Url,
+    pub(crate) fields: Vec<(String, String)>,
+}
+
+impl FromHtml for ShoutForm {
+    fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        ensure!(!super::is_not_found(doc), parse_error::NotFound);
+        ensure!(
+            !super::is_account_disabled(doc),
+            parse_error::AccountDisabled
+        );
+
+        let form_sel = Selector::parse("form").unwrap();
+        let form = doc
+            .select(&form_sel)
+            .find(|f| {
+                super::select_first_elem(*f, "textarea[name='shout']").is_ok()
+            })
+            .ok_or(ParseError::MissingElement {
+                selector: "form textarea[name='shout']",
+            })?;
+
+        let action = match form.value().attr("action") {
+            Some(a) => url.join(a)?,
+            None => url,
+        };
+
+        let fields = super::hidden_fields(form)?;
+
+        Ok(Self { action, fields })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shout_form(body: &str) -> Result<ShoutForm, ParseError> {
+        let txt = format!("<html><body>{}</body></html>", body);
+        let html = Html::parse_document(&txt);
+        let url = Url::parse("https://www.furaffinity.net/user/foo/").unwrap();
+        ShoutForm::from_html(url, &html)
+    }
+
+    #[test]
+    fn shout_form_fields() {
+        let form = shout_form(
+            r#"<form id="search"><input type="hidden" name="q" value="x"></form>
+               <form id="JSForm" method="post" action="/user/foo/">
+                 <input type="hidden" name="action" value="shout">
+                 <input type="hidden" name="key" value="abc123">
+                 <input type="hidden" name="name" value="foo">
+                 <textarea name="shout"></textarea>
+               </form>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            form.action.as_str(),
+            "https://www.furaffinity.net/user/foo/"
+        );
+        assert_eq!(
+            form.fields,
+            [
+                ("action".to_string(), "shout".to_string()),
+                ("key".to_string(), "abc123".to_string()),
+                ("name".to_string(), "foo".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn shout_form_missing() {
+        match shout_form("<p>No shouts here.</p>") {
+            Err(ParseError::MissingElement { .. }) => (),
+            other => panic!("expected MissingElement, got {:?}", other),
+        }
+    }
+}
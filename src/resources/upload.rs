@@ -0,0 +1,97 @@
+use scraper::{ElementRef, Html, Selector};
+
+use snafu::OptionExt;
+
+use super::{parse_error, FromHtml, ParseError};
+
+use url::Url;
+
+fn form_containing<'a>(
+    doc: &'a Html,
+    css: &'static str,
+) -> Result<ElementRef<'a>, ParseError> {
+    let form_sel = Selector::parse("form").unwrap();
+    doc.select(&form_sel)
+        .find(|f| super::select_first_elem(*f, css).is_ok())
+        .context(parse_error::MissingElement { selector: css })
+}
+
+fn action(url: Url, form: ElementRef) -> Result<Url, ParseError> {
+    match form.value().attr("action") {
+        Some(a) => Ok(url.join(a)?),
+        None => Ok(url),
+    }
+}
+
+/// The first step of submitting artwork, where the file is chosen.
+#[derive(Debug, Clone)]
+pub(crate) struct UploadForm {
+    pub(crate) action: Url,
+    pub(crate) fields: Vec<(String, String)>,
+}
+
+impl FromHtml for UploadForm {
+    fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        let form =
+            form_containing(doc, "input[type='file'][name='submission']")?;
+
+        Ok(Self {
+            action: action(url, form)?,
+            fields: super::hidden_fields(form)?,
+        })
+    }
+}
+
+/// The last step of submitting artwork, where the title, description and
+/// the rest are filled in.
+#[derive(Debug, Clone)]
+pub(crate) struct FinalizeForm {
+    pub(crate) action: Url,
+    pub(crate) fields: Vec<(String, String)>,
+}
+
+impl FromHtml for FinalizeForm {
+    fn from_html(url: Url, doc: &Html) -> Result<Self, ParseError> {
+        let form = form_containing(doc, "input[name='title']")?;
+
+        Ok(Self {
+            action: action(url, form)?,
+            fields: super::hidden_fields(form)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upload_then_finalize() {
+        let url = Url::parse("https://www.furaffinity.net/submit/").unwrap();
+
+        let upload = Html::parse_document(
+            r#"<form id="searchbox" action="/search/"></form>
+               <form id="myform" method="post" action="/submit/upload/"
+                     enctype="multipart/form-data">
+                 <input type="hidden" name="key" value="abc123">
+                 <input type="hidden" name="submission_type" value="submission">
+                 <input type="file" name="submission">
+               </form>"#,
+        );
+
+        let form = UploadForm::from_html(url.clone(), &upload).unwrap();
+        assert_eq!(
+            form.action.as_str(),
+            "https://www.furaffinity.net/submit/upload/"
+        );
+        assert_eq!(
+            form.fields,
+            [
+                ("key".to_string(), "abc123".to_string()),
+                ("submission_type".to_string(), "submission".to_string()),
+            ]
+        );
+
+        FinalizeForm::from_html(url, &upload).unwrap_err();
+    }
+}
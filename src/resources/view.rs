@@ -1,45 +1,271 @@
-use chrono::NaiveDateTime;
+use chrono::{Duration, NaiveDateTime};
 
-use crate::html::simplify;
-use crate::keys::{CommentReplyKey, FavKey, FromUrlError, ViewKey};
+use crate::html::{quotes, simplify, Quote};
+use crate::keys::{
+    CommentReplyKey, FavKey, FolderKey, FromUrlError, SearchKey, ViewKey,
+};
+
+use percent_encoding::percent_decode_str;
 
 use scraper::{ElementRef, Html, Selector};
 
+use serde::{Deserialize, Serialize};
+
 use snafu::{ensure, OptionExt};
 
-use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::convert::{Infallible, TryFrom};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
-use super::comment::{CommentContainer, CommentRoot};
+use super::comment::{CommentContainer, CommentRoot, ReplyForm};
 use super::{
-    parse_error, select_first, FromHtml, MiniUser, ParseError, PreviewSize,
-    Rating, Submission, SubmissionKind, UnauthenticatedError,
+    parse_error, select_first, FromHtml, MiniUser, ParseError, Permalink,
+    PreviewSize, Rating, Submission, SubmissionKind, UnauthenticatedError,
 };
 
 use url::Url;
 
-#[derive(Debug, Clone)]
+macro_rules! taxonomy {
+    ($name:ident { $($variant:ident => $text:literal = $id:literal,)* }) => {
+        #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($variant,)*
+            Other(String),
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let txt = match self {
+                    $($name::$variant => $text,)*
+                    $name::Other(txt) => txt,
+                };
+
+                write!(f, "{}", txt)
+            }
+        }
+
+        impl $name {
+            /// FurAffinity's numeric id, as used in its forms. `None` for
+            /// `Other`.
+            pub fn id(&self) -> Option<u16> {
+                match self {
+                    $($name::$variant => Some($id),)*
+                    $name::Other(_) => None,
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(text: &str) -> Result<Self, Infallible> {
+                let parsed = match text {
+                    $($text => $name::$variant,)*
+                    _ => $name::Other(text.to_string()),
+                };
+
+                Ok(parsed)
+            }
+        }
+    };
+}
+
+taxonomy!(Category {
+    All => "All" = 1,
+    ArtworkDigital => "Artwork (Digital)" = 2,
+    ArtworkTraditional => "Artwork (Traditional)" = 3,
+    Cellshading => "Cellshading" = 4,
+    Crafting => "Crafting" = 5,
+    Designs => "Designs" = 6,
+    Flash => "Flash" = 7,
+    Fursuiting => "Fursuiting" = 8,
+    Icons => "Icons" = 9,
+    Mosaics => "Mosaics" = 10,
+    Photography => "Photography" = 14,
+    FoodRecipes => "Food / Recipes" = 32,
+    Sculpting => "Sculpting" = 15,
+    Story => "Story" = 16,
+    Poetry => "Poetry" = 17,
+    Prose => "Prose" = 18,
+    Music => "Music" = 19,
+    Podcasts => "Podcasts" = 20,
+    Skins => "Skins" = 21,
+    Handhelds => "Handhelds" = 22,
+    Resources => "Resources" = 23,
+    Adoptables => "Adoptables" = 24,
+    Auctions => "Auctions" = 25,
+    Contests => "Contests" = 26,
+    CurrentEvents => "Current Events" = 27,
+    Desktops => "Desktops" = 28,
+    Stockart => "Stockart" = 29,
+    Screenshots => "Screenshots" = 30,
+    Scraps => "Scraps" = 31,
+    Wallpaper => "Wallpaper" = 11,
+    YchSale => "YCH / Sale" = 12,
+});
+
+taxonomy!(Type {
+    All => "All" = 1,
+    Abstract => "Abstract" = 2,
+    AnimalRelated => "Animal related (non-anthro)" = 3,
+    Anime => "Anime" = 4,
+    Comics => "Comics" = 5,
+    Doodle => "Doodle" = 6,
+    Fanart => "Fanart" = 7,
+    Fantasy => "Fantasy" = 8,
+    Human => "Human" = 9,
+    Portraits => "Portraits" = 10,
+    Scenery => "Scenery" = 11,
+    StillLife => "Still Life" = 12,
+    Tutorials => "Tutorials" = 13,
+    Miscellaneous => "Miscellaneous" = 14,
+    Babyfur => "Baby fur" = 101,
+    Bondage => "Bondage" = 102,
+    Digimon => "Digimon" = 103,
+    FatFurs => "Fat Furs" = 104,
+    FetishOther => "Fetish Other" = 105,
+    Fursuit => "Fursuit" = 106,
+    GoreMacabre => "Gore / Macabre Art" = 119,
+    Hyper => "Hyper" = 107,
+    Hypnosis => "Hypnosis" = 121,
+    Inflation => "Inflation" = 108,
+    Micro => "Micro" = 109,
+    Muscle => "Muscle" = 110,
+    MyLittlePony => "My Little Pony / Brony" = 111,
+    Paw => "Paw" = 112,
+    Pokemon => "Pokemon" = 113,
+    Pregnancy => "Pregnancy" = 114,
+    Sonic => "Sonic" = 115,
+    Transformation => "Transformation" = 116,
+    TfTg => "TF / TG" = 120,
+    Vore => "Vore" = 117,
+    WaterSports => "Water Sports" = 118,
+    GeneralFurryArt => "General Furry Art" = 100,
+    Techno => "Techno" = 201,
+    Trance => "Trance" = 202,
+    House => "House" = 203,
+    Nineties => "90s" = 204,
+    Eighties => "80s" = 205,
+    Seventies => "70s" = 206,
+    Sixties => "60s" = 207,
+    PreSixties => "Pre-60s" = 208,
+    Classical => "Classical" = 209,
+    GameMusic => "Game Music" = 210,
+    Rock => "Rock" = 211,
+    Pop => "Pop" = 212,
+    Rap => "Rap" = 213,
+    Industrial => "Industrial" = 214,
+    OtherMusic => "Other Music" = 200,
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct View {
     fav_key: Option<FavKey>,
     faved: Option<bool>,
 
     submission: Submission,
+    description_quotes: Vec<Quote>,
     fullview: Url,
+    sample_shown: bool,
     download: Url,
+    download_filename: Option<String>,
 
     category: String,
     type_: String,
+    species: Option<String>,
+    gender: Option<String>,
 
     tags: Vec<String>,
+    tag_links: Vec<(String, SearchKey)>,
+
+    footer_links: Vec<(String, Url)>,
 
     n_views: u64,
     n_comments: u64,
+    n_comments_source: CommentCountSource,
     n_favorites: u64,
 
     posted: NaiveDateTime,
 
+    gallery_nav: GalleryNav,
+
+    comments_enabled: bool,
+    reply_fields: Vec<(String, String)>,
     comments: Vec<CommentContainer>,
 }
 
+/// The kind of submission on a view page, read from the
+/// `page-content-type-*` class FurAffinity puts on `#submission_page`.
+pub fn content_type(doc: &Html) -> Option<SubmissionKind> {
+    let elem = select_first(doc, "#submission_page").ok()?;
+    let class = elem.value().attr("class")?;
+
+    class.split_whitespace().find_map(|c| match c {
+        "page-content-type-flash" => Some(SubmissionKind::Flash),
+        "page-content-type-image" => Some(SubmissionKind::Image),
+        "page-content-type-text" => Some(SubmissionKind::Text),
+        "page-content-type-music" => Some(SubmissionKind::Audio),
+        _ => None,
+    })
+}
+
+/// Where a reader can go from a submission.
+#[derive(
+    Debug, Clone, Default, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
+pub struct GalleryNav {
+    prev: Option<ViewKey>,
+    next: Option<ViewKey>,
+    folder: Option<FolderKey>,
+}
+
+impl GalleryNav {
+    /// The newer submission in the artist's gallery.
+    pub fn prev(&self) -> Option<ViewKey> {
+        self.prev
+    }
+
+    /// The older submission in the artist's gallery.
+    pub fn next(&self) -> Option<ViewKey> {
+        self.next
+    }
+
+    /// The first folder the submission is listed in, skipping any link that
+    /// doesn't parse.
+    pub fn folder(&self) -> Option<&FolderKey> {
+        self.folder.as_ref()
+    }
+}
+
+/// Where [`View::n_comments`] came from.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum CommentCountSource {
+    /// The count in the submission's stats.
+    Stats,
+    /// The stats were missing, so the comments on the page were counted.
+    /// Hidden comments that FA leaves out of the page aren't included.
+    Counted,
+}
+
+/// Views are equal when they are of the same submission, like
+/// [`Submission`].
+impl PartialEq for View {
+    fn eq(&self, other: &Self) -> bool {
+        self.submission == other.submission
+    }
+}
+
+impl Eq for View {}
+
+impl Hash for View {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.submission.hash(state);
+    }
+}
+
 impl TryFrom<&View> for FavKey {
     type Error = UnauthenticatedError;
 
@@ -68,6 +294,12 @@ impl From<View> for CommentReplyKey {
     }
 }
 
+impl Permalink for View {
+    fn permalink(&self) -> Url {
+        ViewKey::from(self).into()
+    }
+}
+
 impl From<&View> for ViewKey {
     fn from(v: &View) -> Self {
         ViewKey {
@@ -101,22 +333,97 @@ impl View {
         &self.submission
     }
 
+    /// Quotes in the description, with their attribution kept separate
+    /// instead of flattened into the simplified HTML.
+    pub fn description_quotes(&self) -> &[Quote] {
+        &self.description_quotes
+    }
+
     pub fn preview(&self, sz: PreviewSize) -> Url {
         self.submission.preview(sz)
     }
 
+    /// The largest image FA shows on the page. For text and audio
+    /// submissions this is a rendered thumbnail, not the submission itself.
     pub fn fullview(&self) -> &Url {
         &self.fullview
     }
 
+    /// Whether the page displays the downscaled preview instead of
+    /// `fullview`, which FA does for large images depending on the
+    /// account's settings. `download` is the original file either way.
+    pub fn is_sample_shown(&self) -> bool {
+        self.sample_shown
+    }
+
+    /// The submitted file, from the page's download link. For text
+    /// submissions this is the document (`.txt`, `.rtf`, `.pdf`, ...), never
+    /// the thumbnail from `fullview`, and for large images it's the original
+    /// even when `fullview` is a downscaled sample.
     pub fn download(&self) -> &Url {
         &self.download
     }
 
+    /// Whether `download` is a document rather than an image.
+    pub fn is_downloadable_document(&self) -> bool {
+        self.submission.kind == SubmissionKind::Text
+    }
+
+    /// The original filename, taken from the last segment of
+    /// [`download`](Self::download) with percent-encoding removed.
+    pub fn download_filename(&self) -> Option<&str> {
+        self.download_filename.as_deref()
+    }
+
+    /// The extension of [`download_filename`](Self::download_filename),
+    /// without the leading dot.
+    pub fn file_extension(&self) -> Option<&str> {
+        let filename = self.download_filename.as_deref()?;
+        match filename.rsplit_once('.') {
+            Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => {
+                Some(ext)
+            }
+            _ => None,
+        }
+    }
+
+    /// A best-effort MIME type for [`download`](Self::download), guessed
+    /// from the file extension or, failing that, the submission kind.
+    pub fn mime_type(&self) -> Option<&'static str> {
+        let ext = self.file_extension().map(str::to_ascii_lowercase);
+
+        let mime = match ext.as_deref() {
+            Some("png") => "image/png",
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("swf") => "application/x-shockwave-flash",
+            Some("mp3") => "audio/mpeg",
+            Some("wav") => "audio/wav",
+            Some("rtf") => "application/rtf",
+            Some("txt") => "text/plain",
+            Some("pdf") => "application/pdf",
+            Some("doc") => "application/msword",
+            Some("odt") => "application/vnd.oasis.opendocument.text",
+            _ => match self.submission.kind {
+                SubmissionKind::Flash => "application/x-shockwave-flash",
+                SubmissionKind::Audio => "audio/mpeg",
+                SubmissionKind::Image | SubmissionKind::Text => return None,
+            },
+        };
+
+        Some(mime)
+    }
+
     pub fn faved(&self) -> Option<bool> {
         self.faved
     }
 
+    /// Whether the page had a fav or unfav link, so the session can change
+    /// the favorite. Always `false` when logged out.
+    pub fn can_favorite(&self) -> bool {
+        self.fav_key.is_some()
+    }
+
     pub fn category(&self) -> &str {
         &self.category
     }
@@ -125,10 +432,42 @@ impl View {
         &self.type_
     }
 
+    pub fn typed_category(&self) -> Category {
+        self.category.parse().unwrap()
+    }
+
+    pub fn typed_type(&self) -> Type {
+        self.type_.parse().unwrap()
+    }
+
+    /// The species from the sidebar, as displayed (e.g. "Unspecified / Any").
+    pub fn species(&self) -> Option<&str> {
+        self.species.as_deref()
+    }
+
+    /// The gender from the sidebar, as displayed (e.g. "Any").
+    pub fn gender(&self) -> Option<&str> {
+        self.gender.as_deref()
+    }
+
     pub fn tags(&self) -> &[String] {
         &self.tags
     }
 
+    /// Tags lower-cased the way FA's search matches them.
+    pub fn tag_slugs(&self) -> Vec<String> {
+        self.tags.iter().map(|t| t.trim().to_lowercase()).collect()
+    }
+
+    /// Each tag with the search it links to.
+    pub fn tag_links(&self) -> &[(String, SearchKey)] {
+        &self.tag_links
+    }
+
+    pub fn footer_links(&self) -> &[(String, Url)] {
+        &self.footer_links
+    }
+
     pub fn n_views(&self) -> u64 {
         self.n_views
     }
@@ -137,31 +476,181 @@ impl View {
         self.n_favorites
     }
 
+    /// Comment count as reported by FurAffinity. Hidden or removed comments
+    /// are still counted here, so this may not match
+    /// [`n_comments_displayed`](Self::n_comments_displayed). If the stats
+    /// are missing from the page, this falls back to counting the comments
+    /// instead; see [`n_comments_source`](Self::n_comments_source).
     pub fn n_comments(&self) -> u64 {
         self.n_comments
     }
 
+    pub fn n_comments_source(&self) -> CommentCountSource {
+        self.n_comments_source
+    }
+
+    /// Number of comment containers actually present on the page.
+    pub fn n_comments_displayed(&self) -> usize {
+        self.comments.len()
+    }
+
+    /// Whether the owner allows comments on this submission, going by the
+    /// notice FurAffinity shows when they don't. When disabled,
+    /// [`comments`](Self::comments) is always empty.
+    pub fn comments_enabled(&self) -> bool {
+        self.comments_enabled
+    }
+
+    /// Hidden fields of the comment form, including any token FurAffinity
+    /// expects back with a reply. Empty when there is no form, like when
+    /// logged out.
+    pub fn reply_fields(&self) -> &[(String, String)] {
+        &self.reply_fields
+    }
+
     pub fn posted(&self) -> NaiveDateTime {
         self.posted
     }
 
+    /// Whether `posted` agrees with the file's CDN timestamp. The timestamp
+    /// moves forward whenever the file is replaced, but never predates the
+    /// submission, so a day of slack is allowed for `posted` being in the
+    /// viewer's timezone. A mismatch usually means the wrong date element
+    /// was read.
+    pub fn dates_consistent(&self) -> bool {
+        self.submission.created() + Duration::days(1) >= self.posted
+    }
+
+    pub fn gallery_nav(&self) -> &GalleryNav {
+        &self.gallery_nav
+    }
+
+    pub fn prev_in_gallery(&self) -> Option<ViewKey> {
+        self.gallery_nav.prev
+    }
+
+    pub fn next_in_gallery(&self) -> Option<ViewKey> {
+        self.gallery_nav.next
+    }
+
+    /// Comments in the order they appear on the page, so that
+    /// `comments()[i].index() == i`.
     pub fn comments(&self) -> &[CommentContainer] {
         &self.comments
     }
 
+    pub fn comments_by_id(&self) -> HashMap<u64, &CommentContainer> {
+        self.comments.iter().map(|c| (c.comment_id, c)).collect()
+    }
+
+    /// The comment with `comment_id` and the comments it replies to,
+    /// outermost first.
+    pub fn comment_thread(
+        &self,
+        comment_id: u64,
+    ) -> Option<Vec<&CommentContainer>> {
+        super::comment::thread(&self.comments, comment_id)
+    }
+
+    /// The users who left visible comments, each listed once in order of
+    /// their first comment.
+    pub fn commenters(&self) -> Vec<&MiniUser> {
+        super::comment::commenters(&self.comments)
+    }
+
+    /// The value of a labelled row in the sidebar's info section, if the
+    /// submission has one.
+    fn sidebar_info(doc: &Html, label: &str) -> Option<String> {
+        let row_sel =
+            Selector::parse(".submission-sidebar section.info > div").unwrap();
+        let label_sel = Selector::parse("strong.highlight").unwrap();
+        let value_sel = Selector::parse("span").unwrap();
+
+        doc.select(&row_sel)
+            .find(|row| {
+                row.select(&label_sel)
+                    .next()
+                    .map(|l| super::text(l) == label)
+                    .unwrap_or(false)
+            })
+            .and_then(|row| row.select(&value_sel).next())
+            .map(super::text)
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Tags from `<meta name="keywords">`, for when the sidebar markup can't
+    /// be parsed. Pages without their own keywords carry the site-wide list,
+    /// which isn't treated as tags.
+    fn meta_keywords(doc: &Html) -> Vec<String> {
+        const SITE_KEYWORDS: &str = concat!(
+            "fur furry furries fursuit fursuits cosplay brony bronies ",
+            "zootopia scalies kemono anthro anthropormophic art online ",
+            "gallery portfolio",
+        );
+
+        let content = match super::select_first(doc, "meta[name='keywords']") {
+            Ok(meta) => meta.value().attr("content").unwrap_or_default(),
+            Err(_) => return Vec::new(),
+        };
+
+        let content = content.trim();
+        if content == SITE_KEYWORDS {
+            return Vec::new();
+        }
+
+        content
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn blocked_rating(doc: &Html) -> Option<Rating> {
+        let message = super::system_message(doc)?;
+        if message.contains("Mature or Adult") {
+            None
+        } else if message.contains("Adult") {
+            Some(Rating::Adult)
+        } else if message.contains("Mature") {
+            Some(Rating::Mature)
+        } else {
+            None
+        }
+    }
+
     fn extract_urls_flash(
         url: &Url,
         doc: &Html,
     ) -> Result<(Url, Url), ParseError> {
-        let id0 = url.path_segments().unwrap().nth(1).unwrap();
+        let id0 = url
+            .path_segments()
+            .and_then(|mut s| s.nth(1))
+            .filter(|s| !s.is_empty())
+            .context(parse_error::IncorrectUrl)?;
 
         let embed = select_first(doc, "object#flash_embed")?;
         let fullview_txt = super::attr(embed, "data")?;
         let fullview = url.join(fullview_txt)?;
 
-        let id1 = fullview.path_segments().unwrap().nth(2).unwrap();
-
-        let preview_txt = format!("//t.facdn.net/{}@200-{}.jpg", id0, id1);
+        let id1 = fullview
+            .path_segments()
+            .and_then(|mut s| s.nth(2))
+            .filter(|s| !s.is_empty())
+            .with_context(|| parse_error::MalformedCdnUrl {
+                url: fullview.clone(),
+                reason: "no timestamp segment",
+            })?;
+
+        // Previews live on the thumbnail shard matching the download shard,
+        // so d2.facdn.net pairs with t2.facdn.net.
+        let thumb_host = fullview
+            .host_str()
+            .and_then(|h| h.strip_prefix('d'))
+            .filter(|h| h.ends_with(".facdn.net"))
+            .map(|h| format!("t{}", h))
+            .unwrap_or_else(|| "t.facdn.net".to_string());
+
+        let preview_txt = format!("//{}/{}@200-{}.jpg", thumb_host, id0, id1);
         let preview = url.join(&preview_txt)?;
 
         Ok((preview, fullview))
@@ -183,15 +672,33 @@ impl View {
 
 impl FromHtml for View {
     fn from_html(url: Url, doc: &Html) -> Result<View, ParseError> {
+        ensure!(!super::is_not_found(doc), parse_error::NotFound);
+        ensure!(
+            !super::is_account_disabled(doc),
+            parse_error::AccountDisabled
+        );
+        ensure!(
+            !super::is_registration_required(&url, doc),
+            parse_error::RegistrationRequired
+        );
+
         let res_subimg = select_first(doc, "img#submissionImg");
 
-        let (preview, fullview) = match res_subimg {
-            Ok(img) => Self::extract_urls(&url, img)?,
+        let (preview, fullview, sample_shown) = match res_subimg {
+            Ok(img) => {
+                let (preview, fullview) = Self::extract_urls(&url, img)?;
+                let shown = url.join(super::attr(img, "src")?)?;
+                let sample_shown = shown != fullview;
+                (preview, fullview, sample_shown)
+            }
             Err(ParseError::MissingElement { .. }) => {
                 if select_first(doc, "#pageid-matureimage-error").is_ok() {
-                    return Err(ParseError::Nsfw);
+                    return Err(ParseError::Nsfw {
+                        rating: Self::blocked_rating(doc),
+                    });
                 }
-                Self::extract_urls_flash(&url, doc)?
+                let (preview, fullview) = Self::extract_urls_flash(&url, doc)?;
+                (preview, fullview, false)
             }
             Err(e) => return Err(e),
         };
@@ -205,23 +712,18 @@ impl FromHtml for View {
         let view_id = view_id_txt.parse()?;
         let comment_root = CommentRoot::View(view_id);
 
-        let kind_elem = select_first(doc, "#submission_page")?;
-        let kind_class = super::attr(kind_elem, "class")?;
-        let kind = if kind_class.contains("page-content-type-flash") {
-            SubmissionKind::Flash
-        } else if kind_class.contains("page-content-type-image") {
-            SubmissionKind::Image
-        } else if kind_class.contains("page-content-type-text") {
-            SubmissionKind::Text
-        } else if kind_class.contains("page-content-type-music") {
-            SubmissionKind::Audio
-        } else {
-            return Err(ParseError::MissingAttribute { attribute: "class" });
-        };
+        select_first(doc, "#submission_page")?;
+        let kind = content_type(doc)
+            .context(parse_error::MissingAttribute { attribute: "class" })?;
 
         let download_elem = select_first(doc, ".download a")?;
         let download_txt = super::attr(download_elem, "href")?;
         let download = url.join(download_txt)?;
+        let download_filename = download
+            .path_segments()
+            .and_then(Iterator::last)
+            .filter(|f| !f.is_empty())
+            .map(|f| percent_decode_str(f).decode_utf8_lossy().into_owned());
 
         let category_elem =
             select_first(doc, ".submission-sidebar span.category-name")?;
@@ -231,13 +733,20 @@ impl FromHtml for View {
             select_first(doc, ".submission-sidebar span.type-name")?;
         let type_ = super::text(type_elem);
 
+        let species = Self::sidebar_info(doc, "Species");
+        let gender = Self::sidebar_info(doc, "Gender");
+
         let views_elem =
             select_first(doc, ".stats-container .views .font-large")?;
         let n_views = super::number(views_elem)?;
 
-        let comments_elem =
-            select_first(doc, ".stats-container .comments .font-large")?;
-        let n_comments = super::number(comments_elem)?;
+        let comments_res =
+            select_first(doc, ".stats-container .comments .font-large");
+        let n_comments_stat = match comments_res {
+            Ok(e) => Some(super::number(e)?),
+            Err(ParseError::MissingElement { .. }) => None,
+            Err(e) => return Err(e),
+        };
 
         let favorites_elem =
             select_first(doc, ".stats-container .favorites .font-large")?;
@@ -260,6 +769,19 @@ impl FromHtml for View {
 
         let description_elem = select_first(doc, ".submission-description")?;
         let description = simplify(&url, description_elem);
+        let description_quotes = quotes(&url, description_elem);
+
+        let footer_sel = Selector::parse(
+            ".submission-description .submission-footer a[href]",
+        )
+        .unwrap();
+        let footer_links = doc
+            .select(&footer_sel)
+            .filter_map(|a| {
+                let href = a.value().attr("href")?;
+                Some((super::text(a), url.join(href).ok()?))
+            })
+            .collect();
 
         let avatar_elem = select_first(doc, ".submission-id-avatar > a > img")?;
         let avatar_txt = super::attr(avatar_elem, "src")?;
@@ -270,19 +792,90 @@ impl FromHtml for View {
             ".submission-id-sub-container > a[href^='/user/']",
         )?;
         let user_href = super::attr(artist_elem, "href")?;
-        let user_slug = user_href[6..user_href.len() - 1].to_string();
+        let user_slug = super::user_slug(user_href)?.to_string();
         let user_name = super::text(artist_elem);
 
         let tag_sel = Selector::parse(".submission-sidebar .tags").unwrap();
-        let tags = doc.select(&tag_sel).map(super::text).collect();
+        let mut tags = Vec::new();
+        let mut tag_links = Vec::new();
+        for tag_elem in doc.select(&tag_sel) {
+            let tag = super::text(tag_elem);
+
+            let href = super::select_first_elem(tag_elem, "a[href]")
+                .ok()
+                .and_then(|a| a.value().attr("href"))
+                .and_then(|h| url.join(h).ok());
+            let key = href
+                .and_then(|h| SearchKey::try_from(h).ok())
+                .unwrap_or_else(|| SearchKey::keyword(&tag));
+
+            tag_links.push((tag.clone(), key));
+            tags.push(tag);
+        }
+
+        if tags.is_empty() {
+            for tag in Self::meta_keywords(doc) {
+                tag_links.push((tag.clone(), SearchKey::keyword(&tag)));
+                tags.push(tag);
+            }
+        }
+
+        // Owners can disable comments, which replaces the form and the
+        // comments with a notice. A section that's merely missing doesn't
+        // count.
+        let notice_sel =
+            Selector::parse(".comments-list > .section-body").unwrap();
+        let comments_enabled = !doc.select(&notice_sel).any(|e| {
+            super::text(e).contains("Comment posting has been disabled")
+        });
+
+        let reply_fields = ReplyForm::fields_of(&url, doc)?;
 
         let comment_sel =
             Selector::parse("#comments-submission .comment_container").unwrap();
         let comments = doc
             .select(&comment_sel)
-            .map(|c| CommentContainer::extract(&url, comment_root, c))
+            .enumerate()
+            .map(|(i, c)| CommentContainer::extract(&url, comment_root, i, c))
             .collect::<Result<Vec<_>, _>>()?;
 
+        let (n_comments, n_comments_source) = match n_comments_stat {
+            Some(n) => (n, CommentCountSource::Stats),
+            None => (comments.len() as u64, CommentCountSource::Counted),
+        };
+
+        let mut gallery_nav = GalleryNav::default();
+
+        let gallery_sel =
+            Selector::parse(".favorite-nav a[href^='/view/']").unwrap();
+        for elem in doc.select(&gallery_sel) {
+            // The navigation is optional, so a link that doesn't parse is
+            // skipped rather than failing the whole page.
+            let key = elem
+                .value()
+                .attr("href")
+                .and_then(|h| url.join(h).ok())
+                .and_then(|h| ViewKey::try_from(h).ok());
+            let key = match key {
+                Some(k) => k,
+                None => continue,
+            };
+
+            match super::text(elem).as_str() {
+                "Prev" => gallery_nav.prev = Some(key),
+                "Next" => gallery_nav.next = Some(key),
+                _ => (),
+            }
+        }
+
+        let folder_sel =
+            Selector::parse(".folder-list-container a[href*='/folder/']")
+                .unwrap();
+        gallery_nav.folder = doc.select(&folder_sel).find_map(|elem| {
+            let href = url.join(elem.value().attr("href")?).ok()?;
+            FolderKey::try_from(href).ok()
+        });
+
         let fav_res = select_first(doc, ".favorite-nav a[href^='/fav/']");
         let unfav_res = select_first(doc, ".favorite-nav a[href^='/unfav/']");
 
@@ -319,33 +912,95 @@ impl FromHtml for View {
             None
         };
 
+        let submission = Submission {
+            kind,
+            view_id,
+            created,
+            cdn,
+            rating,
+            title,
+            description,
+            artist: MiniUser {
+                avatar,
+                name: user_name,
+                slug: user_slug,
+            },
+        };
+
         Ok(Self {
             faved,
             fav_key,
-            submission: Submission {
-                kind,
-                view_id,
-                created,
-                cdn,
-                rating,
-                title,
-                description,
-                artist: MiniUser {
-                    avatar,
-                    name: user_name,
-                    slug: user_slug,
-                },
-            },
+            submission,
+            description_quotes,
             fullview,
+            sample_shown,
             download,
+            download_filename,
             category,
             type_,
+            species,
+            gender,
             tags,
+            tag_links,
+            footer_links,
             n_views,
             n_comments,
+            n_comments_source,
             n_favorites,
             posted,
+            gallery_nav,
+            comments_enabled,
+            reply_fields,
             comments,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind_of(class: &str) -> Option<SubmissionKind> {
+        let text =
+            format!("<div id='submission_page' class='{}'></div>", class);
+        content_type(&Html::parse_document(&text))
+    }
+
+    #[test]
+    fn content_type_from_class() {
+        assert_eq!(
+            kind_of("page-content-type-flash"),
+            Some(SubmissionKind::Flash)
+        );
+        assert_eq!(
+            kind_of("page-content-type-image"),
+            Some(SubmissionKind::Image)
+        );
+        assert_eq!(
+            kind_of("page-content-type-text"),
+            Some(SubmissionKind::Text)
+        );
+        assert_eq!(
+            kind_of("submission-page page-content-type-music"),
+            Some(SubmissionKind::Audio)
+        );
+        assert_eq!(kind_of("page-content-type-video"), None);
+        assert_eq!(kind_of(""), None);
+    }
+
+    #[test]
+    fn content_type_without_submission_page() {
+        let doc = Html::parse_document("<div class='page-content-type-image'>");
+        assert_eq!(content_type(&doc), None);
+    }
+
+    #[test]
+    fn taxonomy_ids() {
+        assert_eq!(Category::All.id(), Some(1));
+        assert_eq!(Category::ArtworkDigital.id(), Some(2));
+        assert_eq!(Category::Wallpaper.id(), Some(11));
+        assert_eq!(Type::GeneralFurryArt.id(), Some(100));
+        assert_eq!(Type::OtherMusic.id(), Some(200));
+        assert_eq!(Category::Other("Goats".into()).id(), None);
+    }
+}
@@ -0,0 +1,605 @@
+use futures::future::{self, BoxFuture};
+use futures::stream::StreamExt;
+
+use labrat::client::{
+    Authentication, Client, HttpTransport, Redirects, RequestError,
+    UploadParams,
+};
+use labrat::keys::{FolderKey, SubmissionsKey, UserKey, ViewKey};
+use labrat::resources::view::{Category, Type, View};
+use labrat::resources::{ParseError, Rating};
+
+use reqwest::header::{HeaderValue, CONTENT_TYPE, COOKIE, LOCATION};
+use reqwest::ResponseBuilderExt;
+
+use std::sync::{Arc, Mutex};
+
+use url::Url;
+
+/// Answers requests with fixture HTML, keyed by path, and remembers the
+/// cookies, content type and body each request was sent with. Streamed
+/// bodies, like multipart uploads, are recorded as `None`.
+#[derive(Debug, Default)]
+struct FixtureTransport {
+    pages: Vec<(&'static str, String)>,
+    posted: Vec<(&'static str, String)>,
+    redirects: Vec<(&'static str, &'static str, &'static str)>,
+    moved: Vec<(&'static str, &'static str)>,
+    cookies: Arc<Mutex<Vec<Option<HeaderValue>>>>,
+    content_types: Arc<Mutex<Vec<Option<HeaderValue>>>>,
+    bodies: Arc<Mutex<Vec<Option<String>>>>,
+}
+
+impl FixtureTransport {
+    fn page<S>(mut self, path: &'static str, html: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.pages.push((path, html.into()));
+        self
+    }
+
+    /// Answer POST requests to `path` with `html`, instead of the page GET
+    /// requests get.
+    fn posted<S>(mut self, path: &'static str, html: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.posted.push((path, html.into()));
+        self
+    }
+
+    /// Serve `html` for `from` as though FurAffinity redirected to `to`.
+    fn redirect(
+        mut self,
+        from: &'static str,
+        to: &'static str,
+        html: &'static str,
+    ) -> Self {
+        self.redirects.push((from, to, html));
+        self
+    }
+
+    /// Answer `from` with a redirect to `to` that isn't followed.
+    fn moved(mut self, from: &'static str, to: &'static str) -> Self {
+        self.moved.push((from, to));
+        self
+    }
+}
+
+impl HttpTransport for FixtureTransport {
+    fn execute(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, reqwest::Result<reqwest::Response>> {
+        self.cookies
+            .lock()
+            .unwrap()
+            .push(request.headers().get(COOKIE).cloned());
+        self.content_types
+            .lock()
+            .unwrap()
+            .push(request.headers().get(CONTENT_TYPE).cloned());
+        self.bodies.lock().unwrap().push(
+            request
+                .body()
+                .and_then(|b| b.as_bytes())
+                .map(|b| String::from_utf8_lossy(b).into_owned()),
+        );
+
+        let mut url = request.url().clone();
+        let path = url.path().to_owned();
+
+        let redirect = self.redirects.iter().find(|(f, _, _)| *f == path);
+        let posted = match *request.method() {
+            reqwest::Method::POST => {
+                self.posted.iter().find(|(p, _)| *p == path)
+            }
+            _ => None,
+        };
+        let (status, body) = if let Some((_, to, html)) = redirect {
+            url = url.join(to).unwrap();
+            (200, html.to_string())
+        } else if let Some((_, html)) = posted {
+            (200, html.clone())
+        } else {
+            match self.pages.iter().find(|(p, _)| *p == path) {
+                Some((_, html)) => (200, html.clone()),
+                None => (404, String::new()),
+            }
+        };
+
+        let mut response = http::Response::builder().status(status).url(url);
+        if let Some((_, to)) = self.moved.iter().find(|(f, _)| *f == path) {
+            response = response.status(302).header(LOCATION, *to);
+        }
+        let response = response.body(body).unwrap();
+
+        Box::pin(future::ready(Ok(response.into())))
+    }
+}
+
+/// Fails to compile if a request can't be spawned onto another thread.
+#[test]
+fn client_futures_are_send() {
+    fn is_send<T: Send>(_: T) {}
+
+    let client = Client::new().unwrap();
+    is_send(client.view(ViewKey { view_id: 1 }));
+    is_send(client.reply("https://www.furaffinity.net/view/1/", "hi"));
+    is_send(client.shout(UserKey { slug: "foo".into() }, "hi"));
+    is_send(client.upload(UploadParams::new("a.png", vec![], "A")));
+
+    let url = Url::parse("https://a.facdn.net/foo.gif").unwrap();
+    is_send(client.fetch_cdn(&url));
+}
+
+#[tokio::test]
+async fn client_view() {
+    let transport = FixtureTransport::default()
+        .page("/view/38351732/", include_str!("resources/view/image.html"));
+    let cookies = transport.cookies.clone();
+
+    let client = Client::builder()
+        .cookies(HeaderValue::from_static("a=1; b=2"))
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let key = ViewKey { view_id: 38351732 };
+    let response = client.view(key).await.unwrap();
+    assert_eq!(response.authentication, Authentication::LoggedIn);
+    assert_eq!(response.page.submission().title(), "F2U Goat Base");
+
+    let public = client.view_public(key).await.unwrap();
+    assert_eq!(public.page.submission().title(), "F2U Goat Base");
+
+    let cookies = cookies.lock().unwrap();
+    assert_eq!(
+        cookies.as_slice(),
+        &[Some(HeaderValue::from_static("a=1; b=2")), None]
+    );
+}
+
+#[tokio::test]
+async fn client_views_zero_concurrency() {
+    let transport = FixtureTransport::default()
+        .page("/view/38351732/", include_str!("resources/view/image.html"));
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let keys = vec![ViewKey { view_id: 38351732 }; 2];
+    let responses: Vec<_> = client.views(keys, 0).collect().await;
+
+    assert_eq!(responses.len(), 2);
+    for response in responses {
+        let response = response.unwrap();
+        assert_eq!(response.page.submission().title(), "F2U Goat Base");
+    }
+}
+
+#[tokio::test]
+async fn client_fav_view() {
+    // FurAffinity redirects back to the submission once the fav is recorded.
+    let transport = FixtureTransport::default()
+        .page("/view/38351732/", include_str!("resources/view/image.html"))
+        .redirect(
+            "/fav/38351732/",
+            "/view/38351732/",
+            include_str!("resources/view/faved.html"),
+        );
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let key = ViewKey { view_id: 38351732 };
+    let before = client.view(key).await.unwrap().page;
+    assert_eq!(before.faved(), Some(false));
+
+    let after = client.fav_view(key).await.unwrap().page;
+    assert_eq!(after.faved(), Some(true));
+    assert_eq!(after.n_favorites(), before.n_favorites() + 1);
+}
+
+#[tokio::test]
+async fn client_submissions() {
+    let transport = FixtureTransport::default().page(
+        "/msg/submissions/old@72/",
+        include_str!("resources/msg/submissions/next.html"),
+    );
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let response = client.submissions(SubmissionsKey::oldest()).await.unwrap();
+    assert!(!response.page.items().is_empty());
+    assert!(response.page.next().is_some());
+}
+
+#[tokio::test]
+async fn client_folder() {
+    let transport = FixtureTransport::default().page(
+        "/gallery/candykittycat/folder/12345/Adopts/",
+        include_str!("resources/folder.html"),
+    );
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let key = FolderKey::new("candykittycat", 12345, "Adopts");
+    let response = client.folder(key).await.unwrap();
+    assert_eq!(response.page.items().len(), 2);
+    assert_eq!(response.page.next().map(FolderKey::page), Some(3));
+}
+
+#[tokio::test]
+async fn client_others() {
+    let transport = FixtureTransport::default().page(
+        "/msg/others",
+        include_str!("resources/msg/others/others.html"),
+    );
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let response = client.others().await.unwrap();
+    assert_eq!(response.page.watches().len(), 6);
+}
+
+#[tokio::test]
+async fn client_registration_required() {
+    let transport = FixtureTransport::default().page(
+        "/view/38351732/",
+        include_str!("resources/view/registered_only.html"),
+    );
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let error = client
+        .view_public(ViewKey { view_id: 38351732 })
+        .await
+        .unwrap_err();
+
+    match error {
+        RequestError::Parse {
+            source: ParseError::RegistrationRequired,
+        } => (),
+        _ => panic!("expected RegistrationRequired error"),
+    }
+}
+
+#[tokio::test]
+async fn client_redirect_not_followed() {
+    let transport = FixtureTransport::default()
+        .moved("/view/1/", "/")
+        .moved("/view/2/", "/login/?ref=/view/2/");
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let error = client.view(ViewKey { view_id: 1 }).await.unwrap_err();
+    match error {
+        RequestError::Parse {
+            source: ParseError::Redirected { to },
+        } => assert_eq!(to.as_str(), "https://www.furaffinity.net/"),
+        _ => panic!("expected Redirected error"),
+    }
+
+    let error = client.view(ViewKey { view_id: 2 }).await.unwrap_err();
+    match error {
+        RequestError::Parse {
+            source: ParseError::RegistrationRequired,
+        } => (),
+        _ => panic!("expected RegistrationRequired error"),
+    }
+}
+
+#[test]
+fn client_redirects_policy() {
+    Client::builder()
+        .redirects(Redirects::Never)
+        .build()
+        .unwrap();
+    Client::builder()
+        .redirects(Redirects::Limit(2))
+        .build()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn client_unsuccessful() {
+    let client = Client::builder()
+        .transport(FixtureTransport::default())
+        .build()
+        .unwrap();
+
+    client.others().await.unwrap_err();
+}
+
+const PROFILE: &str = r#"
+<html><body>
+  <a href="/user/me/"><img class="loggedin_user_avatar" src="//a.facdn.net/me.gif" alt="me"></a>
+  <form id="JSForm" method="post" action="/user/foo/">
+    <input type="hidden" name="action" value="shout">
+    <input type="hidden" name="key" value="abc123">
+    <input type="hidden" name="name" value="foo">
+    <textarea name="shout"></textarea>
+  </form>
+</body></html>
+"#;
+
+const PROFILE_NO_SHOUTS: &str = r#"
+<html><body>
+  <a href="/user/me/"><img class="loggedin_user_avatar" src="//a.facdn.net/me.gif" alt="me"></a>
+</body></html>
+"#;
+
+const PROFILE_LOGGED_OUT: &str = "<html><body></body></html>";
+
+#[tokio::test]
+async fn client_reply() {
+    let page = include_str!("resources/journal/header_footer.html").replace(
+        r#"<input type="hidden" name="replyto" id="form-replyto" value=""/>"#,
+        r#"<input type="hidden" name="replyto" id="form-replyto" value=""/>
+           <input type="hidden" name="key" value="abc123"/>"#,
+    );
+    // The new comment shows up with a higher id than any before it.
+    let after = page.replacen(r#"id="cid:7777777""#, r#"id="cid:7777778""#, 1);
+    let transport = FixtureTransport::default()
+        .page("/journal/7777777/", page)
+        .posted("/journal/7777777/", after);
+    let bodies = transport.bodies.clone();
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let key = "https://www.furaffinity.net/journal/7777777/";
+    let comment_id = client.reply(key, "hello there").await.unwrap();
+    assert_eq!(comment_id, 7777778);
+
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(bodies[0], None);
+    assert_eq!(
+        bodies[1].as_deref(),
+        Some("action=reply&key=abc123&replyto=&reply=hello+there&send=send")
+    );
+}
+
+#[tokio::test]
+async fn client_reply_not_posted() {
+    // The page comes back unchanged, so the user's newest comment is one
+    // they left before.
+    let page = include_str!("resources/journal/header_footer.html");
+    let transport = FixtureTransport::default()
+        .page("/journal/7777777/", page)
+        .posted("/journal/7777777/", page);
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let key = "https://www.furaffinity.net/journal/7777777/";
+    match client.reply(key, "hello there").await.unwrap_err() {
+        RequestError::NotPosted => (),
+        _ => panic!("expected NotPosted error"),
+    }
+}
+
+const SUBMIT: &str = r#"<html><body>
+    <form id="myform" method="post" action="/submit/upload/"
+          enctype="multipart/form-data">
+        <input type="hidden" name="key" value="abc123">
+        <input type="hidden" name="submission_type" value="submission">
+        <input type="file" name="submission">
+    </form>
+</body></html>"#;
+
+const FINALIZE: &str = r#"<html><body>
+    <form id="myform" method="post" action="/submit/finalize/">
+        <input type="hidden" name="key" value="def456">
+        <input type="text" name="title">
+        <textarea name="message"></textarea>
+    </form>
+</body></html>"#;
+
+#[tokio::test]
+async fn client_upload() {
+    let transport = FixtureTransport::default()
+        .page("/submit/", SUBMIT)
+        .page("/submit/upload/", FINALIZE)
+        .redirect("/submit/finalize/", "/view/12345/?upload-successful", "");
+    let bodies = transport.bodies.clone();
+    let content_types = transport.content_types.clone();
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let params = UploadParams::new("goat.png", b"PNG".to_vec(), "Goat")
+        .with_description("A goat.")
+        .with_tags(vec!["goat", "base"])
+        .with_rating(Rating::Mature)
+        .with_category(Category::ArtworkTraditional)
+        .with_theme(Type::Fanart);
+    let key = client.upload(params).await.unwrap();
+    assert_eq!(key, ViewKey { view_id: 12345 });
+
+    let content_types = content_types.lock().unwrap();
+    let upload = content_types[1].as_ref().unwrap().to_str().unwrap();
+    assert!(upload.starts_with("multipart/form-data; boundary="));
+
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(
+        bodies[2].as_deref(),
+        Some(
+            "key=def456&title=Goat&message=A+goat.&keywords=goat+base&\
+             rating=2&cat=3&atype=7&species=1&gender=0"
+        )
+    );
+}
+
+#[tokio::test]
+async fn client_upload_unknown_category() {
+    let transport = FixtureTransport::default().page("/submit/", SUBMIT);
+    let bodies = transport.bodies.clone();
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let params = UploadParams::new("goat.png", b"PNG".to_vec(), "Goat")
+        .with_category(Category::Other("Goats".into()));
+    match client.upload(params).await.unwrap_err() {
+        RequestError::UnknownTaxonomy { name } => assert_eq!(name, "Goats"),
+        _ => panic!("expected UnknownTaxonomy error"),
+    }
+
+    assert!(bodies.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn client_upload_rejected() {
+    let transport = FixtureTransport::default().page("/submit/", SUBMIT).page(
+        "/submit/upload/",
+        r#"<section class="notice-message"><div class="section-body">
+                   The file you uploaded is too large.
+               </div></section>"#,
+    );
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let params = UploadParams::new("goat.png", b"PNG".to_vec(), "Goat");
+    match client.upload(params).await.unwrap_err() {
+        RequestError::Rejected { message } => {
+            assert!(message.contains("too large"))
+        }
+        _ => panic!("expected Rejected error"),
+    }
+}
+
+#[tokio::test]
+async fn client_shout() {
+    let transport = FixtureTransport::default().page("/user/foo/", PROFILE);
+    let bodies = transport.bodies.clone();
+
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    client
+        .shout(UserKey { slug: "foo".into() }, "hi there")
+        .await
+        .unwrap();
+
+    let bodies = bodies.lock().unwrap();
+    assert_eq!(
+        bodies.as_slice(),
+        &[
+            None,
+            Some("action=shout&key=abc123&name=foo&shout=hi+there".to_string())
+        ]
+    );
+}
+
+#[tokio::test]
+async fn client_shout_disabled() {
+    let transport =
+        FixtureTransport::default().page("/user/foo/", PROFILE_NO_SHOUTS);
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let error = client
+        .shout(UserKey { slug: "foo".into() }, "hi")
+        .await
+        .unwrap_err();
+
+    match error {
+        RequestError::ShoutsDisabled => (),
+        _ => panic!("expected ShoutsDisabled error"),
+    }
+}
+
+#[tokio::test]
+async fn client_shout_logged_out() {
+    let transport =
+        FixtureTransport::default().page("/user/foo/", PROFILE_LOGGED_OUT);
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let error = client
+        .shout(UserKey { slug: "foo".into() }, "hi")
+        .await
+        .unwrap_err();
+
+    match error {
+        RequestError::Unauthenticated { .. } => (),
+        _ => panic!("expected Unauthenticated error"),
+    }
+}
+
+#[tokio::test]
+async fn client_require_auth() {
+    let logged_out = include_str!("resources/view/image.html")
+        .replace("loggedin_user_avatar", "");
+
+    let transport =
+        FixtureTransport::default().page("/view/38351732/", logged_out.clone());
+    let client = Client::builder()
+        .require_auth(true)
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let key = ViewKey { view_id: 38351732 };
+    match client.view(key).await.unwrap_err() {
+        RequestError::Unauthenticated { .. } => (),
+        _ => panic!("expected Unauthenticated error"),
+    }
+
+    let public = client.view_public(key).await.unwrap();
+    assert_eq!(public.authentication, Authentication::Anonymous);
+
+    let transport =
+        FixtureTransport::default().page("/view/38351732/", logged_out);
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let response = client.view(key).await.unwrap();
+    assert_eq!(response.authentication, Authentication::Anonymous);
+}
+
+#[tokio::test]
+async fn client_fetch_cdn() {
+    let transport = FixtureTransport::default()
+        .page("/1572271060/candykittycat.gif", "GIF89a");
+    let cookies = transport.cookies.clone();
+
+    let client = Client::builder()
+        .cookies(HeaderValue::from_static("a=1"))
+        .transport(transport)
+        .build()
+        .unwrap();
+
+    let url = Url::parse("https://a2.facdn.net/1572271060/candykittycat.gif")
+        .unwrap();
+    let bytes = client.fetch_cdn(&url).await.unwrap();
+    assert_eq!(&bytes[..], b"GIF89a");
+
+    for off_site in &[
+        "https://example.com/1572271060/candykittycat.gif",
+        "https://facdn.net.example.com/1572271060/candykittycat.gif",
+        "file:///1572271060/candykittycat.gif",
+    ] {
+        let url = Url::parse(off_site).unwrap();
+        match client.fetch_cdn(&url).await.unwrap_err() {
+            RequestError::NotCdn { url: rejected } => {
+                assert_eq!(rejected, url)
+            }
+            _ => panic!("expected NotCdn error"),
+        }
+    }
+
+    let cookies = cookies.lock().unwrap();
+    assert_eq!(cookies.as_slice(), &[Some(HeaderValue::from_static("a=1"))]);
+}
+
+#[tokio::test]
+async fn client_download_off_site() {
+    let transport = FixtureTransport::default();
+    let cookies = transport.cookies.clone();
+    let client = Client::builder().transport(transport).build().unwrap();
+
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+    let html = include_str!("resources/view/image.html")
+        .replace("//d2.facdn.net/art/", "//example.com/art/");
+    let view: View = labrat::parse(url, &html).unwrap();
+
+    match client.download(&view).await.unwrap_err() {
+        RequestError::NotCdn { url } => {
+            assert_eq!(url.host_str(), Some("example.com"))
+        }
+        _ => panic!("expected NotCdn error"),
+    }
+
+    assert!(cookies.lock().unwrap().is_empty());
+}
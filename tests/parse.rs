@@ -1,17 +1,27 @@
 use chrono::NaiveDate;
 
-use labrat::keys::{CommentReplyKey, FavKey, SubmissionsKey};
+use labrat::client::{Authentication, Response};
+use labrat::keys::{
+    CommentReplyKey, FavKey, FavoritesKey, FolderKey, JournalKey, SearchKey,
+    SubmissionsKey, ViewKey,
+};
+use labrat::resources::browse::Browse;
+use labrat::resources::comment::CommentState;
+use labrat::resources::favorites::Favorites;
+use labrat::resources::folder::Folder;
 use labrat::resources::header::Header;
 use labrat::resources::journal::Journal;
 use labrat::resources::msg::others::Others;
 use labrat::resources::msg::submissions::Submissions;
-use labrat::resources::view::View;
+use labrat::resources::view::{Category, CommentCountSource, Type, View};
 use labrat::resources::{
-    FromHtml, ParseError, PreviewSize, Rating, SubmissionKind,
+    FromHtml, ParseError, Permalink, PreviewSize, Rating, Submission,
+    SubmissionKind,
 };
 
 use scraper::Html;
 
+use std::collections::HashSet;
 use std::convert::TryFrom;
 
 use url::Url;
@@ -47,18 +57,45 @@ fn view_image() {
     assert_eq!(submission.artist().name(), "candykittycat");
     assert_eq!(submission.kind(), SubmissionKind::Image);
 
+    let summary = Submission::from(&view);
+    assert_eq!(ViewKey::from(&summary), ViewKey::from(submission));
+
+    let permalink = "https://www.furaffinity.net/view/38351732/";
+    assert_eq!(view.permalink().as_str(), permalink);
+    assert_eq!(summary.permalink().as_str(), permalink);
+    assert_eq!(summary.preview(PreviewSize::Xxl), preview);
+    assert_eq!(summary.title(), submission.title());
+    assert_eq!(
+        CommentReplyKey::from(&summary),
+        CommentReplyKey::from(&view)
+    );
+
     assert_eq!(view.fullview(), &full);
     assert_eq!(view.download(), &full);
+    assert!(!view.is_sample_shown());
+    assert_eq!(
+        view.download_filename(),
+        Some("1600894374.candykittycat_goat_base001.png")
+    );
+    assert_eq!(view.file_extension(), Some("png"));
+    assert_eq!(view.mime_type(), Some("image/png"));
 
     assert_eq!(view.category(), "All");
     assert_eq!(view.type_(), "All");
+    assert_eq!(view.species(), Some("Unspecified / Any"));
+    assert_eq!(view.gender(), Some("Any"));
 
     assert_eq!(view.n_views(), 128);
     assert_eq!(view.n_comments(), 16);
+    assert_eq!(view.n_comments_source(), CommentCountSource::Stats);
     assert_eq!(view.n_favorites(), 25);
 
-    let posted = NaiveDate::from_ymd(2020, 09, 23).and_hms(15, 52, 00);
+    let posted = NaiveDate::from_ymd_opt(2020, 9, 23)
+        .unwrap()
+        .and_hms_opt(15, 52, 0)
+        .unwrap();
     assert_eq!(view.posted(), posted);
+    assert!(view.dates_consistent());
 
     assert_eq!(
         view.tags(),
@@ -75,10 +112,58 @@ fn view_image() {
         ]
     );
 
+    assert_eq!(
+        view.tag_slugs(),
+        [
+            "adopt",
+            "adoptable",
+            "adoptables",
+            "f2u",
+            "free",
+            "to",
+            "use",
+            "goat",
+            "base"
+        ]
+    );
+
+    assert!(view.comments_enabled());
+
+    let commenters = view
+        .commenters()
+        .into_iter()
+        .map(|u| u.slug())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        commenters,
+        [
+            "luminaria",
+            "candykittycat",
+            "deltasearcher",
+            "lamont786",
+            "jeanquinn",
+            "moonmutt.",
+            "nighter3d",
+            "sleepyhani",
+        ]
+    );
     assert_eq!(view.n_comments(), view.comments().len() as u64);
+    assert_eq!(view.n_comments_displayed(), view.comments().len());
+
+    let tag_links = view.tag_links();
+    assert_eq!(tag_links.len(), view.tags().len());
+    assert_eq!(
+        tag_links[0],
+        ("adopt".to_string(), SearchKey::keyword("adopt"))
+    );
 
     let comment_container = &view.comments()[0];
     let key = CommentReplyKey::from(comment_container);
+    assert_eq!(
+        comment_container.root_view_key(),
+        Some(ViewKey::from(view.submission()))
+    );
+    assert_eq!(comment_container.root_journal_key(), None);
     let exp = CommentReplyKey::try_from(
         "https://www.furaffinity.net/view/38351732/#cid:150154279",
     )
@@ -87,7 +172,10 @@ fn view_image() {
     assert_eq!(comment_container.depth(), 0);
 
     let comment = comment_container.comment().unwrap();
-    let commented = NaiveDate::from_ymd(2020, 09, 23).and_hms(15, 59, 00);
+    let commented = NaiveDate::from_ymd_opt(2020, 9, 23)
+        .unwrap()
+        .and_hms_opt(15, 59, 0)
+        .unwrap();
     assert_eq!(comment.posted(), commented);
     assert_eq!(comment.parent_id(), None);
 
@@ -96,6 +184,7 @@ fn view_image() {
     assert_eq!(comment.commenter().avatar(), &cavatar);
     assert_eq!(comment.commenter().name(), "Luminaria");
     assert_eq!(comment.commenter().slug(), "luminaria");
+    assert_eq!(comment.commenter_key().slug, "luminaria");
 
     let fav_key = FavKey::try_from(&view).unwrap();
     let exp_fav = FavKey::try_from(
@@ -104,6 +193,390 @@ fn view_image() {
     .unwrap();
     assert_eq!(fav_key, exp_fav);
     assert_eq!(view.faved(), Some(false));
+
+    let prev = ViewKey { view_id: 38351843 };
+    let next = ViewKey { view_id: 38211932 };
+    assert_eq!(view.prev_in_gallery(), Some(prev));
+    assert_eq!(view.next_in_gallery(), Some(next));
+
+    let nav = view.gallery_nav();
+    assert_eq!(nav.prev(), Some(prev));
+    assert_eq!(nav.next(), Some(next));
+    let folder = nav.folder().unwrap();
+    assert_eq!(folder.user, "candykittycat");
+    assert_eq!(folder.folder_id, 145943);
+    assert_eq!(folder.name_slug, "Stuff");
+
+    assert!(view.footer_links().is_empty());
+}
+
+#[test]
+fn view_gallery_nav_malformed() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html").replace(
+        r#"<a href="/view/38351843/" class="button standard mobile-fix">Prev</a>"#,
+        r#"<a href="/view/goat/" class="button standard mobile-fix">Prev</a>"#,
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+    assert_eq!(view.prev_in_gallery(), None);
+    assert_eq!(view.next_in_gallery(), Some(ViewKey { view_id: 38211932 }));
+}
+
+#[test]
+fn view_gallery_nav_folder_malformed() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    // A broken entry ahead of the real one is skipped.
+    let text = include_str!("resources/view/image.html").replacen(
+        "<h3>Listed in Folders</h3>",
+        r#"<h3>Listed in Folders</h3>
+           <div><a href="/gallery/candykittycat/folder/stuff/">Stuff</a></div>"#,
+        1,
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url.clone(), &html).unwrap();
+    let folder = view.gallery_nav().folder().unwrap();
+    assert_eq!(folder.folder_id, 145943);
+
+    let text = text.replace(
+        "/gallery/candykittycat/folder/145943/Stuff/",
+        "/gallery/candykittycat/folder/stuff/",
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+    assert_eq!(view.gallery_nav().folder(), None);
+    assert!(view.gallery_nav().prev().is_some());
+}
+
+#[test]
+fn view_artist_href_without_slash() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html").replace(
+        r#"By <a href="/user/candykittycat/">"#,
+        r#"By <a href="/user/candykittycat">"#,
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    assert_eq!(view.submission().artist().slug(), "candykittycat");
+}
+
+#[test]
+fn view_without_species_or_gender() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html")
+        .replace(r#"<strong class="highlight">Species</strong>"#, "")
+        .replace(r#"<strong class="highlight">Gender</strong>"#, "");
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    assert_eq!(view.species(), None);
+    assert_eq!(view.gender(), None);
+}
+
+#[test]
+fn view_tags_from_meta_keywords() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html")
+        .replace(r#"<span class="tags">"#, r#"<span class="tag">"#)
+        .replace(
+            r#"content="fur furry furries fursuit"#,
+            r#"content="goat base" data-site="fur furry furries fursuit"#,
+        );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    assert_eq!(view.tags(), &["goat", "base"]);
+    assert_eq!(view.tag_links()[0].1, SearchKey::keyword("goat"));
+}
+
+#[test]
+fn view_tags_ignore_site_keywords() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html")
+        .replace(r#"<span class="tags">"#, r#"<span class="tag">"#);
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    assert!(view.tags().is_empty());
+}
+
+#[test]
+fn view_faved() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/faved.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    assert_eq!(view.faved(), Some(true));
+    assert_eq!(view.n_favorites(), 26);
+    assert!(view.can_favorite());
+
+    // Equality is by submission, so the unfaved page is the same view.
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+    let text = include_str!("resources/view/image.html");
+    let before = View::from_html(url, &Html::parse_document(text)).unwrap();
+    assert_eq!(before, view);
+
+    let fav_key = FavKey::try_from(&view).unwrap();
+    let exp_fav = FavKey::try_from(
+        Url::parse(
+            "https://www.furaffinity.net/unfav/38351732/?key=........................................",
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(fav_key, exp_fav);
+}
+
+#[test]
+fn view_sample_shown() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/sample.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    let full = Url::parse(concat!(
+        "https://d2.facdn.net/art/candykittycat/1600894374/",
+        "1600894374.candykittycat_goat_base001.png"
+    ))
+    .unwrap();
+
+    assert!(view.is_sample_shown());
+    assert_eq!(view.fullview(), &full);
+    assert_eq!(view.download(), &full);
+}
+
+#[test]
+fn view_download_is_original() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/sample.html").replace(
+        r#"data-fullview-src="//d2.facdn.net/art/candykittycat/1600894374/1600894374.candykittycat_goat_base001.png""#,
+        r#"data-fullview-src="//d2.facdn.net/art/candykittycat/1600894374/1600894374.candykittycat_goat_base001.sample.jpg""#,
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    let original = Url::parse(concat!(
+        "https://d2.facdn.net/art/candykittycat/1600894374/",
+        "1600894374.candykittycat_goat_base001.png"
+    ))
+    .unwrap();
+    let sample = Url::parse(concat!(
+        "https://d2.facdn.net/art/candykittycat/1600894374/",
+        "1600894374.candykittycat_goat_base001.sample.jpg"
+    ))
+    .unwrap();
+
+    assert_eq!(view.download(), &original);
+    assert_eq!(view.fullview(), &sample);
+    assert_ne!(view.download(), view.fullview());
+}
+
+#[test]
+fn view_serde_round_trip() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    let json = serde_json::to_string(&view).unwrap();
+    let cached: View = serde_json::from_str(&json).unwrap();
+
+    let (before, after) = (view.submission(), cached.submission());
+    assert_eq!(
+        before.preview(PreviewSize::Xxl),
+        after.preview(PreviewSize::Xxl)
+    );
+    assert_eq!(before.created(), after.created());
+    assert_eq!(before.title(), after.title());
+    assert_eq!(before.description(), after.description());
+    assert_eq!(before.rating(), after.rating());
+    assert_eq!(before.kind(), after.kind());
+    assert_eq!(before.artist().slug(), after.artist().slug());
+    assert_eq!(before.artist().avatar(), after.artist().avatar());
+
+    assert_eq!(view.fullview(), cached.fullview());
+    assert_eq!(view.download(), cached.download());
+    assert_eq!(view.tags(), cached.tags());
+    assert_eq!(view.tag_links(), cached.tag_links());
+    assert_eq!(view.posted(), cached.posted());
+    assert_eq!(view.n_views(), cached.n_views());
+    assert_eq!(view.next_in_gallery(), cached.next_in_gallery());
+    assert_eq!(view.comments_enabled(), cached.comments_enabled());
+    assert_eq!(view.comments().len(), cached.comments().len());
+
+    for (b, a) in view.comments().iter().zip(cached.comments()) {
+        assert_eq!(b.comment_id(), a.comment_id());
+        assert_eq!(b.depth(), a.depth());
+        assert_eq!(b.state(), a.state());
+        assert_eq!(
+            b.comment().map(|c| c.text()),
+            a.comment().map(|c| c.text())
+        );
+        assert_eq!(
+            b.comment().map(|c| c.posted()),
+            a.comment().map(|c| c.posted())
+        );
+    }
+}
+
+#[test]
+fn view_comments_disabled() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/no_comments.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    assert!(!view.comments_enabled());
+    assert!(view.comments().is_empty());
+    assert_eq!(view.n_comments(), 16);
+}
+
+#[test]
+fn view_comments_section_missing() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    // Without the notice, a missing section doesn't mean comments are off.
+    let text = include_str!("resources/view/image.html").replacen(
+        r#"<div id="comments-submission">"#,
+        "<div>",
+        1,
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    assert!(view.comments_enabled());
+    assert!(view.comments().is_empty());
+}
+
+#[test]
+fn view_preview_keeps_cdn_shard() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    let sizes = [
+        PreviewSize::Xxxs,
+        PreviewSize::Xxs,
+        PreviewSize::Xs,
+        PreviewSize::S,
+        PreviewSize::M,
+        PreviewSize::L,
+        PreviewSize::Xl,
+        PreviewSize::Xxl,
+        PreviewSize::Xxxl,
+    ];
+
+    for sz in sizes.iter().copied() {
+        let expected = format!(
+            "https://t2.facdn.net/38351732@{}-1600894374.jpg",
+            sz.pixels()
+        );
+        assert_eq!(view.preview(sz).as_str(), expected);
+    }
+}
+
+#[test]
+fn view_footer_links() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/footer.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    let links: Vec<_> = view
+        .footer_links()
+        .iter()
+        .map(|(t, u)| (t.as_str(), u.as_str()))
+        .collect();
+
+    assert_eq!(
+        links,
+        [
+            ("Commission info", "https://example.com/commissions"),
+            (
+                "candykittycat",
+                "https://www.furaffinity.net/user/candykittycat/"
+            ),
+            ("Twitter", "https://twitter.com/candykittycat"),
+        ]
+    );
+}
+
+#[test]
+fn view_footer_links_malformed() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/footer.html").replacen(
+        "https://twitter.com/candykittycat",
+        "https://[twitter.com/candykittycat",
+        1,
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    let links: Vec<_> = view
+        .footer_links()
+        .iter()
+        .map(|(t, _)| t.as_str())
+        .collect();
+
+    assert_eq!(links, ["Commission info", "candykittycat"]);
+}
+
+#[test]
+fn view_edited_comment() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/edited_comment.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    let c0 = view.comments()[0].comment().unwrap();
+    let posted = NaiveDate::from_ymd_opt(2020, 9, 23)
+        .unwrap()
+        .and_hms_opt(15, 59, 0)
+        .unwrap();
+    let edited = NaiveDate::from_ymd_opt(2020, 9, 23)
+        .unwrap()
+        .and_hms_opt(16, 12, 0)
+        .unwrap();
+    assert_eq!(c0.posted(), posted);
+    assert_eq!(c0.edited(), Some(edited));
+
+    let c1 = view.comments()[1].comment().unwrap();
+    assert_eq!(c1.edited(), None);
 }
 
 #[test]
@@ -115,6 +588,8 @@ fn view_story() {
 
     let view = View::from_html(url, &html).unwrap();
 
+    assert_eq!(view.gallery_nav().folder(), None);
+
     let preview =
         Url::parse("https://t2.facdn.net/37432007@400-1595836340.jpg").unwrap();
 
@@ -144,6 +619,9 @@ fn view_story() {
 
     assert_eq!(view.fullview(), &fullview);
     assert_eq!(view.download(), &download);
+    assert!(view.is_downloadable_document());
+    assert_eq!(view.file_extension(), Some("rtf"));
+    assert_eq!(view.mime_type(), Some("application/rtf"));
 
     assert_eq!(view.category(), "Story");
     assert_eq!(view.type_(), "All");
@@ -152,7 +630,10 @@ fn view_story() {
     assert_eq!(view.n_comments(), 15);
     assert_eq!(view.n_favorites(), 25);
 
-    let posted = NaiveDate::from_ymd(2020, 07, 27).and_hms(2, 52, 00);
+    let posted = NaiveDate::from_ymd_opt(2020, 7, 27)
+        .unwrap()
+        .and_hms_opt(2, 52, 0)
+        .unwrap();
     assert_eq!(view.posted(), posted);
 
     assert_eq!(
@@ -180,6 +661,14 @@ fn view_story() {
 
     assert_eq!(view.n_comments(), view.comments().len() as u64);
 
+    for (i, c) in view.comments().iter().enumerate() {
+        assert_eq!(c.index(), i);
+    }
+
+    let by_id = view.comments_by_id();
+    assert_eq!(by_id.len(), view.comments().len());
+    assert_eq!(by_id[&148693442].index(), 4);
+
     let comment_container = &view.comments()[4];
     let id = CommentReplyKey::from(comment_container);
     let exp = CommentReplyKey::try_from(
@@ -190,7 +679,10 @@ fn view_story() {
     assert_eq!(comment_container.depth(), 0);
 
     let comment = comment_container.comment().unwrap();
-    let commented = NaiveDate::from_ymd(2020, 7, 28).and_hms(12, 26, 00);
+    let commented = NaiveDate::from_ymd_opt(2020, 7, 28)
+        .unwrap()
+        .and_hms_opt(12, 26, 0)
+        .unwrap();
     assert_eq!(comment.posted(), commented);
     assert_eq!(comment.parent_id(), None);
 
@@ -219,7 +711,7 @@ fn view_flash() {
     let view = View::from_html(url, &html).unwrap();
 
     let preview =
-        Url::parse("https://t.facdn.net/10801070@200-1494747184.jpg").unwrap();
+        Url::parse("https://t2.facdn.net/10801070@200-1494747184.jpg").unwrap();
 
     let full = Url::parse(concat!(
         "https://d2.facdn.net/art/jasonafex/1494747184/",
@@ -241,15 +733,24 @@ fn view_flash() {
 
     assert_eq!(view.fullview(), &full);
     assert_eq!(view.download(), &full);
+    assert_eq!(view.file_extension(), Some("swf"));
+    assert_eq!(view.mime_type(), Some("application/x-shockwave-flash"));
 
     assert_eq!(view.category(), "Flash");
     assert_eq!(view.type_(), "General Furry Art");
+    assert_eq!(view.typed_category(), Category::Flash);
+    assert_eq!(view.typed_type(), Type::GeneralFurryArt);
+    assert_eq!(view.species(), Some("Reptilian (Other)"));
+    assert_eq!(view.gender(), Some("Male"));
 
     assert_eq!(view.n_views(), 88524);
     assert_eq!(view.n_comments(), 76);
     assert_eq!(view.n_favorites(), 1860);
 
-    let posted = NaiveDate::from_ymd(2013, 06, 09).and_hms(4, 33, 00);
+    let posted = NaiveDate::from_ymd_opt(2013, 6, 9)
+        .unwrap()
+        .and_hms_opt(4, 33, 0)
+        .unwrap();
     assert_eq!(view.posted(), posted);
 
     assert_eq!(
@@ -294,10 +795,26 @@ fn view_flash() {
     assert_eq!(comment_container.depth(), 2);
 
     let comment = comment_container.comment().unwrap();
-    let commented = NaiveDate::from_ymd(2013, 6, 16).and_hms(0, 31, 00);
+    let commented = NaiveDate::from_ymd_opt(2013, 6, 16)
+        .unwrap()
+        .and_hms_opt(0, 31, 0)
+        .unwrap();
     assert_eq!(comment.posted(), commented);
     assert_eq!(comment.parent_id(), Some(70788912));
 
+    let parent = CommentReplyKey::try_from(
+        "https://www.furaffinity.net/view/10801070/#cid:70788912",
+    )
+    .unwrap();
+    assert_eq!(comment_container.parent_key(), Some(parent));
+
+    let thread = view.comment_thread(70791506).unwrap();
+    let depths: Vec<_> = thread.iter().map(|c| c.depth()).collect();
+    assert_eq!(depths, vec![0, 1, 2]);
+    assert_eq!(thread[1].comment_id(), 70788912);
+    assert_eq!(thread[2].index(), 6);
+    assert!(view.comment_thread(1).is_none());
+
     let cavatar =
         Url::parse("https://a2.facdn.net/1468877932/matrixg.gif").unwrap();
     assert_eq!(comment.commenter().avatar(), &cavatar);
@@ -354,15 +871,23 @@ fn view_music() {
 
     assert_eq!(view.fullview(), &fullview);
     assert_eq!(view.download(), &download);
+    assert!(!view.is_downloadable_document());
+    assert_eq!(view.file_extension(), Some("mp3"));
+    assert_eq!(view.mime_type(), Some("audio/mpeg"));
 
     assert_eq!(view.category(), "Music");
     assert_eq!(view.type_(), "Fetish Other");
+    assert_eq!(view.typed_category(), Category::Music);
+    assert_eq!(view.typed_type(), Type::FetishOther);
 
     assert_eq!(view.n_views(), 1810);
     assert_eq!(view.n_comments(), 22);
     assert_eq!(view.n_favorites(), 51);
 
-    let posted = NaiveDate::from_ymd(2019, 12, 15).and_hms(12, 48, 00);
+    let posted = NaiveDate::from_ymd_opt(2019, 12, 15)
+        .unwrap()
+        .and_hms_opt(12, 48, 0)
+        .unwrap();
     assert_eq!(view.posted(), posted);
 
     assert_eq!(
@@ -403,49 +928,282 @@ fn view_nsfw() {
     let error = View::from_html(url, &html).unwrap_err();
 
     match error {
-        ParseError::Nsfw => (),
+        ParseError::Nsfw { rating: None } => (),
         _ => panic!("expected Nsfw error"),
     }
 }
 
 #[test]
-fn view_header() {
-    let url = Url::parse("https://www.furaffinity.net/view/34229773/").unwrap();
-
-    let text = include_str!("resources/view/music.html");
-    let html = Html::parse_document(text);
-
-    let header = Header::from_html(url, &html).unwrap();
+fn view_nsfw_adult() {
+    let url = Url::parse("https://www.furaffinity.net/view/38375319/").unwrap();
 
-    assert_eq!(header.me().name(), "aFakeUser");
-    assert_eq!(header.me().slug(), "aFakeUser");
+    let text = include_str!("resources/view/nsfw.html")
+        .replace("Mature or Adult", "Adult");
+    let html = Html::parse_document(&text);
 
-    let avatar =
-        Url::parse("https://a.facdn.net/1424255659/aFakeUser.gif").unwrap();
-    assert_eq!(header.me().avatar(), &avatar);
+    let error = View::from_html(url, &html).unwrap_err();
 
-    let notifs = header.notifications();
-    assert_eq!(notifs.notes, 0);
-    assert_eq!(notifs.journals, 7577);
-    assert_eq!(notifs.trouble_tickets, 0);
-    assert_eq!(notifs.submissions, 357);
-    assert_eq!(notifs.comments, 0);
-    assert_eq!(notifs.watches, 0);
-    assert_eq!(notifs.favorites, 0);
+    match error {
+        ParseError::Nsfw {
+            rating: Some(Rating::Adult),
+        } => (),
+        _ => panic!("expected Nsfw error with Adult rating"),
+    }
 }
 
 #[test]
-fn msg_submissions_next() {
-    let url =
-        Url::parse("https://www.furaffinity.net/msg/submissions/").unwrap();
+fn view_not_found() {
+    let url = Url::parse("https://www.furaffinity.net/view/38375319/").unwrap();
 
-    let text = include_str!("resources/msg/submissions/next.html");
+    let text = include_str!("resources/view/not_found.html");
     let html = Html::parse_document(text);
 
-    let page = Submissions::from_html(url, &html).unwrap();
+    let error = View::from_html(url, &html).unwrap_err();
 
-    let next_url = Url::parse(
-        "https://www.furaffinity.net/msg/submissions/new~12345678@72/",
+    match error {
+        ParseError::NotFound => (),
+        _ => panic!("expected NotFound error"),
+    }
+}
+
+#[test]
+fn view_account_disabled() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/account_disabled.html");
+    let html = Html::parse_document(text);
+
+    let error = View::from_html(url, &html).unwrap_err();
+
+    match error {
+        ParseError::AccountDisabled => (),
+        _ => panic!("expected AccountDisabled error"),
+    }
+}
+
+#[test]
+fn view_registration_required() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/registered_only.html");
+    let html = Html::parse_document(text);
+
+    let error = View::from_html(url, &html).unwrap_err();
+
+    match error {
+        ParseError::RegistrationRequired => (),
+        _ => panic!("expected RegistrationRequired error"),
+    }
+}
+
+#[test]
+fn view_inconsistent_date() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html").replace(
+        "title=\"Sep 23, 2020 03:52 PM\"",
+        "title=\"Sep 23, 2021 03:52 PM\"",
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+    assert!(!view.dates_consistent());
+}
+
+#[test]
+fn view_download_filename_encoded() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html").replace(
+        "<div class=\"download\"><a href=\"//d2.facdn.net/art/candykittycat/1600894374/1600894374.candykittycat_goat_base001.png\">",
+        "<div class=\"download\"><a href=\"//d2.facdn.net/art/candykittycat/1600894374/1600894374.candykittycat_goat%20base%C3%A9.png\">",
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+    assert_eq!(
+        view.download_filename(),
+        Some("1600894374.candykittycat_goat base\u{e9}.png")
+    );
+}
+
+#[test]
+fn view_comment_count_fallback() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/no_comment_stat.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+    assert_eq!(view.n_comments_source(), CommentCountSource::Counted);
+    assert_eq!(view.n_comments(), view.n_comments_displayed() as u64);
+    assert_eq!(view.n_views(), 128);
+}
+
+#[test]
+fn view_logged_out_cannot_favorite() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+
+    let text = include_str!("resources/view/image.html")
+        .replace("/fav/38351732/?key=", "/login/?key=");
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+    assert_eq!(view.faved(), None);
+    assert!(!view.can_favorite());
+}
+
+#[test]
+fn response_authentication() {
+    let url = Url::parse("https://www.furaffinity.net/view/34229773/").unwrap();
+
+    let text = include_str!("resources/view/music.html");
+    let html = Html::parse_document(text);
+    let response = Response::<View>::from_html(url.clone(), &html).unwrap();
+    assert!(response.is_authenticated());
+    assert!(response.header.is_some());
+
+    let text = text.replace("loggedin_user_avatar", "");
+    let html = Html::parse_document(&text);
+    let response = Response::<View>::from_html(url, &html).unwrap();
+    assert_eq!(response.authentication, Authentication::Anonymous);
+    assert!(!response.is_authenticated());
+}
+
+#[test]
+fn view_comment_states() {
+    let url = Url::parse("https://www.furaffinity.net/view/10801070/").unwrap();
+
+    let text = include_str!("resources/view/flash.html");
+    let html = Html::parse_document(text);
+
+    let view = View::from_html(url, &html).unwrap();
+    let by_id = view.comments_by_id();
+
+    assert_eq!(by_id[&70530036].state(), &CommentState::RemovedByUser);
+    assert!(by_id[&70530036].comment().is_none());
+    assert_eq!(by_id[&70538171].state(), &CommentState::RemovedByAdmin);
+    assert_eq!(view.comments()[0].state(), &CommentState::Visible);
+}
+
+#[test]
+fn view_comment_unknown_placeholder() {
+    let url = Url::parse("https://www.furaffinity.net/view/10801070/").unwrap();
+
+    let text = include_str!("resources/view/flash.html").replacen(
+        "<strong>Comment hidden by its owner</strong>",
+        "<strong>Comment hidden for review</strong>",
+        1,
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+    let by_id = view.comments_by_id();
+
+    assert_eq!(
+        by_id[&70530036].state(),
+        &CommentState::Other("Comment hidden for review".into())
+    );
+    assert!(by_id[&70530036].comment().is_none());
+    assert_eq!(by_id[&70538171].state(), &CommentState::RemovedByAdmin);
+}
+
+#[test]
+fn view_flash_preview_shard() {
+    let url = Url::parse("https://www.furaffinity.net/view/10801070/").unwrap();
+
+    let text = include_str!("resources/view/flash.html").replace(
+        "//d2.facdn.net/art/jasonafex/",
+        "//d.facdn.net/art/jasonafex/",
+    );
+    let html = Html::parse_document(&text);
+
+    let view = View::from_html(url, &html).unwrap();
+
+    let preview =
+        Url::parse("https://t.facdn.net/10801070@200-1494747184.jpg").unwrap();
+    assert_eq!(view.submission().preview(PreviewSize::M), preview);
+    assert_eq!(view.fullview().host_str(), Some("d.facdn.net"));
+}
+
+#[test]
+fn view_flash_truncated_cdn_url() {
+    let url = Url::parse("https://www.furaffinity.net/view/10801070/").unwrap();
+
+    let text = include_str!("resources/view/flash.html").replace(
+        concat!(
+            r#"data="//d2.facdn.net/art/jasonafex/1494747184/"#,
+            r#"1370770387.jasonafex_severus_coil_hypno_stuffing.swf""#
+        ),
+        r#"data="//d2.facdn.net/art/jasonafex""#,
+    );
+    let html = Html::parse_document(&text);
+
+    let error = View::from_html(url, &html).unwrap_err();
+
+    match error {
+        ParseError::MalformedCdnUrl { reason, .. } => {
+            assert_eq!(reason, "no timestamp segment");
+        }
+        _ => panic!("expected MalformedCdnUrl error"),
+    }
+}
+
+#[test]
+fn view_header_sfw() {
+    let url = Url::parse("https://www.furaffinity.net/view/34229773/").unwrap();
+
+    let text = include_str!("resources/view/music.html").replace(
+        r#"id="sfw-toggle-mobile" class="slider-toggle" "#,
+        r#"id="sfw-toggle-mobile" class="slider-toggle" checked"#,
+    );
+    let html = Html::parse_document(&text);
+
+    let header = Header::from_html(url, &html).unwrap();
+    assert_eq!(header.content_filter(), Rating::General);
+}
+
+#[test]
+fn view_header() {
+    let url = Url::parse("https://www.furaffinity.net/view/34229773/").unwrap();
+
+    let text = include_str!("resources/view/music.html");
+    let html = Html::parse_document(text);
+
+    let header = Header::from_html(url, &html).unwrap();
+
+    assert_eq!(header.me().name(), "aFakeUser");
+    assert_eq!(header.me().slug(), "aFakeUser");
+
+    let avatar =
+        Url::parse("https://a.facdn.net/1424255659/aFakeUser.gif").unwrap();
+    assert_eq!(header.me().avatar(), &avatar);
+    assert_eq!(header.content_filter(), Rating::Adult);
+
+    let notifs = header.notifications();
+    assert_eq!(notifs.notes, 0);
+    assert_eq!(notifs.journals, 7577);
+    assert_eq!(notifs.trouble_tickets, 0);
+    assert_eq!(notifs.submissions, 357);
+    assert_eq!(notifs.comments, 0);
+    assert_eq!(notifs.watches, 0);
+    assert_eq!(notifs.favorites, 0);
+    assert_eq!(notifs.total(), 7934);
+    assert!(!notifs.is_empty());
+}
+
+#[test]
+fn msg_submissions_next() {
+    let url =
+        Url::parse("https://www.furaffinity.net/msg/submissions/").unwrap();
+
+    let text = include_str!("resources/msg/submissions/next.html");
+    let html = Html::parse_document(text);
+
+    let page = Submissions::from_html(url, &html).unwrap();
+
+    let next_url = Url::parse(
+        "https://www.furaffinity.net/msg/submissions/new~12345678@72/",
     )
     .unwrap();
     let next = SubmissionsKey::try_from(next_url).unwrap();
@@ -476,6 +1234,119 @@ fn msg_submissions_prev() {
     assert_eq!(page.next(), None);
 }
 
+#[test]
+fn msg_submissions_groupings() {
+    let url =
+        Url::parse("https://www.furaffinity.net/msg/submissions/").unwrap();
+
+    let text = include_str!("resources/msg/submissions/next.html");
+    let html = Html::parse_document(text);
+
+    let page = Submissions::from_html(url, &html).unwrap();
+    let items = page.items();
+
+    let ratings = page.ratings_summary();
+    assert_eq!(ratings.values().sum::<usize>(), items.len());
+    for rating in ratings.keys() {
+        let count = items.iter().filter(|s| s.rating() == *rating).count();
+        assert_eq!(ratings[rating], count);
+    }
+
+    let by_artist = page.by_artist();
+    assert_eq!(by_artist.values().map(Vec::len).sum::<usize>(), items.len());
+
+    let first = &by_artist[items[0].artist().slug()];
+    assert_eq!(ViewKey::from(first[0]), ViewKey::from(&items[0]));
+    assert!(first
+        .iter()
+        .all(|s| s.artist().slug() == items[0].artist().slug()));
+}
+
+#[test]
+fn browse() {
+    let url = Url::parse("https://www.furaffinity.net/browse/").unwrap();
+
+    let text = include_str!("resources/browse.html");
+    let html = Html::parse_document(text);
+
+    let page = Browse::from_html(url, &html).unwrap();
+    let items = page.items();
+
+    assert_eq!(items.len(), 72);
+    assert_eq!(ViewKey::from(&items[0]).view_id, 12345678);
+    assert_eq!(items[0].title(), "Dusk Kigu // New Profile ID");
+    assert_eq!(items[0].artist().slug(), "taintedstar");
+    assert_eq!(items[1].rating(), Rating::Adult);
+}
+
+#[test]
+fn folder() {
+    let url = Url::parse(
+        "https://www.furaffinity.net/gallery/candykittycat/folder/12345/Adopts/2/",
+    )
+    .unwrap();
+
+    let text = include_str!("resources/folder.html");
+    let html = Html::parse_document(text);
+
+    let page = Folder::from_html(url, &html).unwrap();
+    let items = page.items();
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(ViewKey::from(&items[0]).view_id, 12345678);
+    assert_eq!(items[1].title(), "Halloween Pack");
+    assert_eq!(items[1].rating(), Rating::Adult);
+    assert!(items.iter().all(|i| i.artist().slug() == "candykittycat"));
+
+    let prev = page.prev().unwrap();
+    assert_eq!(prev.folder_id, 12345);
+    assert_eq!(prev.page(), 1);
+
+    let next = FolderKey::new("candykittycat", 12345, "Adopts").with_page(3);
+    assert_eq!(page.next(), Some(&next));
+}
+
+#[test]
+fn browse_dedup_by_view_id() {
+    let url = Url::parse("https://www.furaffinity.net/browse/").unwrap();
+
+    let text = include_str!("resources/browse.html");
+    let html = Html::parse_document(text);
+
+    let page = Browse::from_html(url, &html).unwrap();
+    let items = page.items();
+
+    let unique: HashSet<&Submission> = items.iter().chain(items).collect();
+    assert_eq!(unique.len(), items.len());
+    assert_ne!(items[0], items[1]);
+}
+
+#[test]
+fn favorites() {
+    let url =
+        Url::parse("https://www.furaffinity.net/favorites/tehkey/").unwrap();
+
+    let text = include_str!("resources/favorites.html");
+    let html = Html::parse_document(text);
+
+    let page = Favorites::from_html(url, &html).unwrap();
+
+    assert_eq!(page.items().len(), 72);
+    assert_eq!(page.items()[0].title(), "Dusk Kigu // New Profile ID");
+
+    let next = FavoritesKey::try_from(
+        "https://www.furaffinity.net/favorites/tehkey/1012345601/next",
+    )
+    .unwrap();
+    let prev = FavoritesKey::try_from(
+        "https://www.furaffinity.net/favorites/tehkey/1012345678/prev",
+    )
+    .unwrap();
+
+    assert_eq!(page.next(), Some(&next));
+    assert_eq!(page.prev(), Some(&prev));
+}
+
 #[test]
 fn journal_header_footer() {
     let url =
@@ -488,25 +1359,262 @@ fn journal_header_footer() {
 
     assert_eq!(page.title(), "Testing Comment Depth");
     assert_eq!(page.journal_id(), 7777777);
-    assert_eq!(page.n_comments(), 27);
+    assert_eq!(page.n_comments_reported(), 27);
+    assert!(page.header().unwrap().contains("Header."));
+    assert!(page.footer().unwrap().contains("Footer."));
+    assert_eq!(
+        page.permalink().as_str(),
+        "https://www.furaffinity.net/journal/7777777/"
+    );
 
-    let posted = NaiveDate::from_ymd(2020, 09, 24).and_hms(20, 38, 00);
+    let posted = NaiveDate::from_ymd_opt(2020, 9, 24)
+        .unwrap()
+        .and_hms_opt(20, 38, 0)
+        .unwrap();
     assert_eq!(page.posted(), posted);
+    assert_eq!(page.edited(), None);
 
     let comments = page.comments();
-    assert_eq!(comments.len() as u64, page.n_comments());
+
+    // Nothing in this thread is hidden, so the counts agree.
+    assert_eq!(comments.len() as u64, page.n_comments_reported());
 
     let cc0 = &comments[0];
     assert_eq!(cc0.depth(), 0);
 
     let c0 = cc0.comment().unwrap();
     assert_eq!(c0.parent_id(), None);
+    assert_eq!(cc0.parent_key(), None);
+
+    let parent = CommentReplyKey::try_from(
+        "https://www.furaffinity.net/journal/7777777/#cid:7777777",
+    )
+    .unwrap();
+    assert_eq!(comments[1].parent_key(), Some(parent));
     assert!(c0.text().contains("Top level"));
+    assert_eq!(c0.commenter().slug(), "aFakeUser");
+    assert_eq!(c0.commenter().name(), "aFakeUser");
 
-    let c0_posted = NaiveDate::from_ymd(2020, 09, 24).and_hms(20, 38, 00);
+    let reply_fields = page.reply_fields();
+    assert!(reply_fields.contains(&("action".into(), "reply".into())));
+
+    let c0_posted = NaiveDate::from_ymd_opt(2020, 9, 24)
+        .unwrap()
+        .and_hms_opt(20, 38, 0)
+        .unwrap();
     assert_eq!(c0.posted(), c0_posted);
 }
 
+#[test]
+fn journal_comment_slug_from_link() {
+    let url =
+        Url::parse("https://www.furaffinity.net/journal/7777777").unwrap();
+
+    let text = include_str!("resources/journal/header_footer.html")
+        .replace(r#"alt="aFakeUser""#, r#"alt="A Fake User""#);
+    let html = Html::parse_document(&text);
+
+    let page = Journal::from_html(url, &html).unwrap();
+
+    let slugs: Vec<_> = page.commenters().iter().map(|u| u.slug()).collect();
+    assert_eq!(slugs, ["aFakeUser", "kakurady"]);
+}
+
+#[test]
+fn journal_edited() {
+    let url =
+        Url::parse("https://www.furaffinity.net/journal/7777777").unwrap();
+
+    let text = include_str!("resources/journal/edited.html");
+    let html = Html::parse_document(text);
+
+    let page = Journal::from_html(url, &html).unwrap();
+
+    let posted = NaiveDate::from_ymd_opt(2020, 9, 24)
+        .unwrap()
+        .and_hms_opt(20, 38, 0)
+        .unwrap();
+    assert_eq!(page.posted(), posted);
+
+    let edited = NaiveDate::from_ymd_opt(2020, 9, 25)
+        .unwrap()
+        .and_hms_opt(1, 2, 0)
+        .unwrap();
+    assert_eq!(page.edited(), Some(edited));
+
+    let quotes = page.content_quotes();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(quotes[0].author(), Some("A person"));
+    assert_eq!(quotes[0].body(), "Something they said");
+}
+
+#[test]
+fn journal_no_comments() {
+    let url =
+        Url::parse("https://www.furaffinity.net/journal/7777777/").unwrap();
+
+    let text = include_str!("resources/journal/no_comments.html");
+    let html = Html::parse_document(text);
+
+    let page = Journal::from_html(url, &html).unwrap();
+
+    assert_eq!(page.title(), "Testing Comment Depth");
+    assert_eq!(page.n_comments_reported(), 0);
+    assert!(page.comments().is_empty());
+}
+
+#[test]
+fn journal_not_found() {
+    let url =
+        Url::parse("https://www.furaffinity.net/journal/7777777/").unwrap();
+
+    let text = include_str!("resources/journal/not_found.html");
+    let html = Html::parse_document(text);
+
+    let error = Journal::from_html(url, &html).unwrap_err();
+
+    match error {
+        ParseError::NotFound => (),
+        _ => panic!("expected NotFound error"),
+    }
+}
+
+#[test]
+fn msg_others_watch_no_date() {
+    let url = Url::parse("https://www.furaffinity.net/msg/others/").unwrap();
+
+    let text = include_str!("resources/msg/others/watch_no_date.html");
+    let html = Html::parse_document(text);
+
+    let page = Others::from_html_strict(url, &html).unwrap();
+
+    let watches = page.watches();
+    assert_eq!(watches.len(), 6);
+
+    let w1 = watches[1].watch().unwrap();
+    assert_eq!(w1.user().slug(), "afakeuser02");
+    assert_eq!(w1.when(), None);
+}
+
+#[test]
+fn msg_others_skips_malformed_rows() {
+    let url = Url::parse("https://www.furaffinity.net/msg/others/").unwrap();
+
+    let text = include_str!("resources/msg/others/others.html").replacen(
+        r#"name="watches[]" value="12345""#,
+        r#"name="watches[]" value="bogus""#,
+        1,
+    );
+    let html = Html::parse_document(&text);
+
+    Others::from_html_strict(url.clone(), &html).unwrap_err();
+
+    let page = Others::from_html(url, &html).unwrap();
+    assert_eq!(page.errors().len(), 1);
+    assert_eq!(page.watches().len(), 5);
+    assert_eq!(
+        page.watches()[0].watch().unwrap().user().slug(),
+        "afakeuser02"
+    );
+    assert_eq!(page.shouts().len(), 3);
+}
+
+#[test]
+fn msg_others_serde_round_trip() {
+    let url = Url::parse("https://www.furaffinity.net/msg/others/").unwrap();
+
+    let text = include_str!("resources/msg/others/others.html");
+    let html = Html::parse_document(text);
+
+    let page = Others::from_html_strict(url, &html).unwrap();
+
+    let json = serde_json::to_string(&page).unwrap();
+    let cached: Others = serde_json::from_str(&json).unwrap();
+
+    assert!(cached.errors().is_empty());
+
+    assert_eq!(page.watches().len(), cached.watches().len());
+    for (b, a) in page.watches().iter().zip(cached.watches()) {
+        assert_eq!(b.watch_id(), a.watch_id());
+        assert_eq!(
+            b.watch().map(|w| w.user().slug()),
+            a.watch().map(|w| w.user().slug())
+        );
+        assert_eq!(
+            b.watch().and_then(|w| w.when()),
+            a.watch().and_then(|w| w.when())
+        );
+    }
+
+    assert_eq!(page.comments().len(), cached.comments().len());
+    for (b, a) in page.comments().iter().zip(cached.comments()) {
+        assert_eq!(b.comment_id(), a.comment_id());
+        assert_eq!(b.is_journal(), a.is_journal());
+
+        let (b, a) = match (b.comment(), a.comment()) {
+            (Some(b), Some(a)) => (b, a),
+            (b, a) => {
+                assert_eq!(b.is_none(), a.is_none());
+                continue;
+            }
+        };
+        assert_eq!(b.as_view_key(), a.as_view_key());
+        assert_eq!(b.as_journal_key(), a.as_journal_key());
+        assert_eq!(CommentReplyKey::from(b), CommentReplyKey::from(a));
+        assert_eq!(b.title(), a.title());
+        assert_eq!(b.posted(), a.posted());
+    }
+
+    assert_eq!(page.shouts().len(), cached.shouts().len());
+    for (b, a) in page.shouts().iter().zip(cached.shouts()) {
+        assert_eq!(b.shout_id(), a.shout_id());
+        assert_eq!(
+            b.shout().map(|s| s.posted()),
+            a.shout().map(|s| s.posted())
+        );
+    }
+
+    assert_eq!(page.journals().len(), cached.journals().len());
+    for (b, a) in page.journals().iter().zip(cached.journals()) {
+        assert_eq!(JournalKey::from(b), JournalKey::from(a));
+        assert_eq!(b.title(), a.title());
+        assert_eq!(b.author().slug(), a.author().slug());
+    }
+
+    assert_eq!(page.favorites().len(), cached.favorites().len());
+    for (b, a) in page.favorites().iter().zip(cached.favorites()) {
+        assert_eq!(b.favorite_id(), a.favorite_id());
+        assert_eq!(ViewKey::from(b), ViewKey::from(a));
+        assert_eq!(b.when(), a.when());
+    }
+}
+
+#[test]
+fn msg_submissions_serde_round_trip() {
+    let url =
+        Url::parse("https://www.furaffinity.net/msg/submissions/").unwrap();
+
+    let text = include_str!("resources/msg/submissions/next.html");
+    let html = Html::parse_document(text);
+
+    let page = Submissions::from_html(url, &html).unwrap();
+
+    let json = serde_json::to_string(&page).unwrap();
+    let cached: Submissions = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(page.next(), cached.next());
+    assert_eq!(page.prev(), cached.prev());
+    assert_eq!(page.items().len(), cached.items().len());
+
+    for (b, a) in page.items().iter().zip(cached.items()) {
+        assert_eq!(ViewKey::from(b), ViewKey::from(a));
+        assert_eq!(b.title(), a.title());
+        assert_eq!(b.rating(), a.rating());
+        assert_eq!(b.kind(), a.kind());
+        assert_eq!(b.preview(PreviewSize::L), a.preview(PreviewSize::L));
+    }
+}
+
 #[test]
 fn msg_others() {
     let url = Url::parse("https://www.furaffinity.net/msg/others/").unwrap();
@@ -514,7 +1622,7 @@ fn msg_others() {
     let text = include_str!("resources/msg/others/others.html");
     let html = Html::parse_document(text);
 
-    let page = Others::from_html(url, &html).unwrap();
+    let page = Others::from_html_strict(url, &html).unwrap();
 
     let watches = page.watches();
     assert_eq!(watches.len(), 6);
@@ -525,6 +1633,15 @@ fn msg_others() {
     assert_eq!(w0.user().slug(), "afakeuser00");
     assert_eq!(w0.user().name(), "aFakeUser00");
     assert_eq!(w0.user().avatar(), &a0);
+    assert_eq!(
+        w0.when(),
+        Some(
+            NaiveDate::from_ymd_opt(2020, 5, 25)
+                .unwrap()
+                .and_hms_opt(21, 58, 0)
+                .unwrap()
+        )
+    );
 
     let w1 = watches[1].watch().unwrap();
     let a1 = Url::parse("https://a.facdn.net/12345/afakeuser02.gif").unwrap();
@@ -546,6 +1663,10 @@ fn msg_others() {
     assert_eq!(c1.title(), "Testing Comment Depth");
     assert_eq!(c1.as_view_key(), None);
     c1.as_journal_key().unwrap();
+    assert_eq!(
+        c1.permalink().as_str(),
+        "https://www.furaffinity.net/journal/12345/#cid:12345"
+    );
 
     assert_eq!(c1.author().slug(), "afakeuser05");
     assert_eq!(c1.author().name(), "aFakeUser05");
@@ -570,7 +1691,10 @@ fn msg_others() {
     assert_eq!(j9.title(), "Fall/Winter Icons?");
     assert_eq!(
         j9.posted(),
-        NaiveDate::from_ymd(2020, 11, 12).and_hms(16, 45, 00)
+        NaiveDate::from_ymd_opt(2020, 11, 12)
+            .unwrap()
+            .and_hms_opt(16, 45, 0)
+            .unwrap()
     );
     assert_eq!(j9.author().name(), "Silberry");
     assert_eq!(j9.author().slug(), "silberry");
@@ -582,8 +1706,27 @@ fn msg_others() {
     assert_eq!(f0.title(), "Bewbs");
     assert_eq!(
         f0.when(),
-        NaiveDate::from_ymd(2020, 04, 21).and_hms(15, 45, 00)
+        NaiveDate::from_ymd_opt(2020, 4, 21)
+            .unwrap()
+            .and_hms_opt(15, 45, 0)
+            .unwrap()
     );
     assert_eq!(f0.user().name(), "aFakeUser06");
     assert_eq!(f0.user().slug(), "afakeuser06");
 }
+
+#[test]
+fn parse_saved_page() {
+    let url = Url::parse("https://www.furaffinity.net/view/38351732/").unwrap();
+    let text = include_str!("resources/view/image.html");
+
+    let response: Response<View> = labrat::parse(url.clone(), text).unwrap();
+    assert_eq!(response.authentication, Authentication::LoggedIn);
+    assert_eq!(response.page.submission().title(), "F2U Goat Base");
+
+    let text = include_str!("resources/view/nsfw.html");
+    match labrat::parse::<View>(url, text).unwrap_err() {
+        ParseError::Nsfw { .. } => (),
+        _ => panic!("expected Nsfw error"),
+    }
+}